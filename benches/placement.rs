@@ -0,0 +1,33 @@
+use std::hint::black_box;
+
+use battleship_rs::{
+  game::{Board, Difficulty, Rule},
+  sim,
+};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// the same boards and games on every run so results compare
+const SEED: u64 = 1;
+
+fn placement(c: &mut Criterion) {
+  let mut rng = StdRng::seed_from_u64(SEED);
+  c.bench_function("place a fleet", |b| {
+    b.iter(|| black_box(Board::new(true, &mut rng)))
+  });
+}
+
+fn games(c: &mut Criterion) {
+  let mut group = c.benchmark_group("computer vs computer");
+  // reported as games per second
+  group.throughput(Throughput::Elements(1));
+  for difficulty in [Difficulty::Easy, Difficulty::Hard].iter().copied() {
+    group.bench_function(difficulty.to_string(), |b| {
+      b.iter(|| black_box(sim::simulate(Rule::Fury, difficulty, SEED)))
+    });
+  }
+  group.finish();
+}
+
+criterion_group!(benches, placement, games);
+criterion_main!(benches);
@@ -1,94 +1,370 @@
 use std::{
-  collections::BTreeSet,
-  fmt,
+  convert::TryFrom,
+  path::{Path, PathBuf},
   time::{Duration, Instant},
 };
 
-use termion::event::Key;
-use tui::{
-  style::{Color, Style},
-  widgets::{Block, BorderType, Borders},
+use termion::event::{Key, MouseButton};
+
+use super::{
+  error::GameError,
+  game::{
+    Board, Clue, Coord, Coordinate, Difficulty, Game, Outcome, Rule, ShotAnalysis, Sinking, Status,
+    Volley, COLS, ROWS,
+  },
+  keymap::{Action, KeyMap},
+  render::{CellView, Palette},
 };
 
-use super::game::{Coordinate, Difficulty, Game, Rule, Status, COLS, ROWS};
+pub enum AppState {
+  Playing,
+  /// result screen of a finished game
+  GameOver,
+  /// boards hidden between hot seat turns until the next player takes over
+  HandOff,
+  /// playing with the opponent's fleet faintly shown and a prompt for each step
+  Tutorial(TutorialStep),
+  /// stepping through the volleys of a finished game
+  Replay {
+    step: usize,
+    autoplay: bool,
+    game: Box<Game>,
+  },
+}
+
+/// tutorial prompts, each advanced by the result of the player's next volley
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TutorialStep {
+  FirstShot,
+  SinkShip,
+  ReadStatus,
+}
+
+impl TutorialStep {
+  pub fn prompt(self) -> &'static str {
+    match self {
+      TutorialStep::FirstShot => {
+        "fire your first shot: select a cell with <space>, fire with <enter>"
+      }
+      TutorialStep::SinkShip => {
+        "the faint outlines are enemy ships, hit every cell of one to sink it"
+      }
+      TutorialStep::ReadStatus => {
+        "the status line lists every enemy ship hit so far, fire again to finish the tutorial"
+      }
+    }
+  }
+
+  /// the step after a volley, `None` once the tutorial is over
+  fn next(self, sank: bool) -> Option<Self> {
+    match self {
+      TutorialStep::FirstShot => Some(TutorialStep::SinkShip),
+      TutorialStep::SinkShip if sank => Some(TutorialStep::ReadStatus),
+      TutorialStep::SinkShip => Some(TutorialStep::SinkShip),
+      TutorialStep::ReadStatus => None,
+    }
+  }
+}
+
+/// ticks a limited ability waits for its confirmation before it's dropped
+const CONFIRM_TICKS: u16 = 12;
 
 pub struct App {
   pub title: String,
   pub should_quit: bool,
+  pub keymap: KeyMap,
+  /// ticks the easy computer waits before firing back, harder ones think longer
+  pub bot_delay: u16,
+  /// jump the cursor to the next cell not fired at after each volley
+  pub auto_advance: bool,
+  /// words instead of emoji for screen readers
+  pub accessible: bool,
+  /// boards drawn as one grid with lines between the cells
+  pub grid_lines: bool,
+  /// two people take turns at the same keyboard instead of playing the computer
+  pub hot_seat: bool,
+  /// save the game here after every turn
+  pub autosave: Option<PathBuf>,
+  /// match clock, when it runs out whoever sank more ships wins
+  pub blitz: Option<Duration>,
+  /// after a volley with a miss, show what was known around the missed cell
+  pub explain_misses: bool,
+  /// once the opponent is down to one ship, outline where it lies
+  pub reveal_last_ship: bool,
+  pub palette: Palette,
   //pub enhanced_graphics: bool,
   pub message: String,
   pub frame_count: u16,
   pub start_time: Instant,
   game: Game,
-  active_column: usize,
-  active_row: usize,
-  selected_coordinates: BTreeSet<Coordinate>,
+  /// (row, column) of each player's cursor, the first is used in every game and the second only in
+  /// hot seat games
+  cursors: [Coordinate; 2],
+  /// staged shots in the order they were selected
+  pending_shots: Vec<Coordinate>,
   duration: Option<Duration>,
+  state: AppState,
+  show_help: bool,
+  /// quit was pressed mid-game, waiting for `y` to leave or `n` to stay
+  pending_quit: bool,
+  /// when the user's current turn began
+  turn_start: Instant,
+  /// time the user took for each volley fired
+  turn_durations: Vec<Duration>,
+  /// ticks since the computer's turn began
+  bot_wait: u16,
+  /// limited ability waiting for the fire key, with the ticks left to confirm it
+  confirming: Option<(Action, u16)>,
+  /// cell of the ship picked to move, the next maneuver key moves it to the cursor
+  maneuver_from: Option<Coord>,
+  /// rotation the ship being moved is turned to, unchanged until the rotate key
+  maneuver_rotation: Option<u16>,
+  /// suggested shot on the opponent board, until the turn ends
+  hint: Option<Coord>,
+  /// last non-empty message and the volley count when it arrived, kept after the alert fades
+  last_message: Option<(String, usize)>,
+  /// missed shot explained in an overlay until the next key
+  explained_miss: Option<Coord>,
 }
 
 impl App {
-  pub fn new(title: String, rule: Rule, difficulty: Difficulty) -> Self {
+  pub fn new(title: String, game: Game) -> Self {
     App {
       title,
       should_quit: false,
+      keymap: KeyMap::default(),
+      bot_delay: 4,
+      auto_advance: false,
+      accessible: false,
+      grid_lines: false,
+      hot_seat: false,
+      autosave: None,
+      blitz: None,
+      explain_misses: false,
+      reveal_last_ship: false,
+      palette: Palette::default(),
       //enhanced_graphics: true,
-      active_column: 0,
-      active_row: 0,
-      selected_coordinates: BTreeSet::new(),
-      game: Game::new(rule, difficulty),
+      cursors: [(0, 0); 2],
+      pending_shots: vec![],
+      game,
       message: String::default(),
       frame_count: 0,
       start_time: Instant::now(),
       duration: None,
+      state: AppState::Playing,
+      show_help: false,
+      pending_quit: false,
+      turn_start: Instant::now(),
+      turn_durations: vec![],
+      bot_wait: 0,
+      confirming: None,
+      maneuver_from: None,
+      maneuver_rotation: None,
+      hint: None,
+      last_message: None,
+      explained_miss: None,
     }
   }
 
   fn on_up(&mut self) {
-    if let Some(active_row) = self.active_row.checked_sub(1) {
-      self.active_row = active_row;
+    let cursor = self.cursor_mut();
+    if let Some(active_row) = cursor.0.checked_sub(1) {
+      cursor.0 = active_row;
     }
   }
 
   fn on_down(&mut self) {
-    if self.active_row < ROWS - 1 {
-      self.active_row += 1;
+    let cursor = self.cursor_mut();
+    if cursor.0 < ROWS - 1 {
+      cursor.0 += 1;
     }
   }
 
   fn on_right(&mut self) {
-    if self.active_column < COLS - 1 {
-      self.active_column += 1;
+    let cursor = self.cursor_mut();
+    if cursor.1 < COLS - 1 {
+      cursor.1 += 1;
     }
   }
 
   fn on_left(&mut self) {
-    if let Some(active_column) = self.active_column.checked_sub(1) {
-      self.active_column = active_column;
+    let cursor = self.cursor_mut();
+    if let Some(active_column) = cursor.1.checked_sub(1) {
+      cursor.1 = active_column;
     }
   }
 
   fn on_select(&mut self) {
     if !self.game.is_won() {
-      if self.is_selected((self.active_row, self.active_column)) {
-        self
-          .selected_coordinates
-          .remove(&(self.active_row, self.active_column));
+      let active = self.active();
+      if self.is_pending(active) {
+        self.pending_shots.retain(|c| *c != active);
       } else if self.is_valid_rule() {
-        self
-          .selected_coordinates
-          .insert((self.active_row, self.active_column));
+        self.pending_shots.push(active);
       } else {
         self.message = "Maximum shots for rule selected".into()
       }
     }
   }
 
+  /// drop the most recently staged shot
+  fn on_unselect(&mut self) {
+    self.pending_shots.pop();
+  }
+
+  /// drop all staged shots without firing
+  fn on_clear_selection(&mut self) {
+    self.pending_shots.clear();
+    self.maneuver_from = None;
+    self.maneuver_rotation = None;
+  }
+
+  /// highlight the shot the hard computer would take next
+  fn on_hint(&mut self) {
+    self.message = if self.game.is_won() || !self.is_players_turn() {
+      "Not your turn".into()
+    } else {
+      match self.game.hint() {
+        Ok(at) => {
+          self.hint = Some(at);
+          format!("Hint: try {}, {} left", at, self.hints_left())
+        }
+        Err(msg) => msg,
+      }
+    };
+  }
+
+  /// the first press picks your ship under the cursor, the second moves that cell to the cursor
+  fn on_maneuver(&mut self) {
+    let at = match Coord::try_from(self.active()) {
+      Ok(at) => at,
+      Err(_) => return,
+    };
+    self.message = if self.game.is_won() || !self.is_players_turn() {
+      "Not your turn".into()
+    } else if !self.game.can_maneuver() {
+      "No maneuver left".into()
+    } else if let Some(from) = self.maneuver_from.take() {
+      match self.game.maneuver(from, at, self.maneuver_rotation.take()) {
+        Ok(msg) => {
+          self.end_turn();
+          msg
+        }
+        Err(msg) => msg,
+      }
+    } else {
+      let board = self.game.player_by_turn(self.viewer()).player_board();
+      match board.cell_info(at).ship {
+        Some(ship) => {
+          self.maneuver_from = Some(at);
+          self.maneuver_rotation = None;
+          format!(
+            "Maneuver: move the cursor and press <m> to move the {} there, <o> turns it, <esc> cancels",
+            ship.ship_type.name()
+          )
+        }
+        None => "Maneuver: put the cursor on one of your ships first".into(),
+      }
+    };
+  }
+
+  pub fn can_maneuver(&self) -> bool {
+    self.game.can_maneuver()
+  }
+
+  /// turn the ship being moved a quarter, the preview shows right away whether it fits
+  fn on_rotate(&mut self) {
+    let from = match self.maneuver_from {
+      Some(from) => from,
+      None => return,
+    };
+    let board = self.game.player_by_turn(self.viewer()).player_board();
+    let rotation = match self.maneuver_rotation.or_else(|| board.rotation_at(from)) {
+      Some(rotation) => rotation % 360 + 90,
+      None => return,
+    };
+    self.maneuver_rotation = Some(rotation);
+    self.message = match self.maneuver_fits() {
+      Some(true) => "Maneuver: turned, it fits here".into(),
+      _ => "Maneuver: turned, it doesn't fit here".into(),
+    };
+  }
+
+  /// cells the ship being moved would cover at the cursor and whether it fits there
+  pub fn maneuver_preview(&self) -> Option<(Vec<Coordinate>, bool)> {
+    let from = self.maneuver_from?;
+    let to = Coord::try_from(self.active()).ok()?;
+    self
+      .game
+      .player_by_turn(self.viewer())
+      .player_board()
+      .move_preview(from, to, self.maneuver_rotation)
+      .ok()
+  }
+
+  pub fn maneuver_fits(&self) -> Option<bool> {
+    self.maneuver_preview().map(|(_, fits)| fits)
+  }
+
+  fn on_scuttle(&mut self) {
+    let at = match Coord::try_from(self.active()) {
+      Ok(at) => at,
+      Err(_) => return,
+    };
+    self.message = if self.game.is_won() || !self.is_players_turn() {
+      "Not your turn".into()
+    } else {
+      match self.game.scuttle(at) {
+        Ok(msg) => {
+          self.end_turn();
+          msg
+        }
+        Err(msg) => msg,
+      }
+    };
+  }
+
+  pub fn can_scuttle(&self) -> bool {
+    self.game.can_scuttle()
+  }
+
   fn on_fire(&mut self) {
-    let msg = if self.selected_coordinates.is_empty() {
+    let msg = if self.pending_shots.is_empty() {
       "Select opponent coordinates to hit".into()
-    } else if !self.game.is_won() && self.game.is_user_turn() {
-      let msg = self.game.fire(&self.selected_coordinates, false);
-      self.selected_coordinates = BTreeSet::new();
+    } else if !self.game.is_won() && self.is_players_turn() {
+      let shots = self
+        .pending_shots
+        .iter()
+        .filter_map(|c| Coord::try_from(*c).ok())
+        .collect();
+      let viewer = self.viewer();
+      let sunk = self.game.sinkings().len();
+      let msg = self.game.fire(&shots, false);
+      self.pending_shots.clear();
+      if self.explain_misses {
+        let tracking = self.game.player_by_turn(viewer).opponent_board();
+        self.explained_miss = shots
+          .iter()
+          .copied()
+          .find(|c| tracking.cell_info(*c).status == Status::Miss);
+      }
+      if let AppState::Tutorial(step) = self.state {
+        let sank = self.game.sinkings().len() > sunk;
+        self.state = step
+          .next(sank)
+          .map_or(AppState::Playing, AppState::Tutorial);
+      }
+      if self.auto_advance {
+        let next = self
+          .game
+          .player_by_turn(viewer)
+          .opponent_board()
+          .next_unfired_from(self.active());
+        if let Some(next) = next {
+          self.cursors[viewer] = next;
+        }
+      }
+      self.end_turn();
       msg
     } else {
       "Not your turn".into()
@@ -102,16 +378,350 @@ impl App {
     );
   }
 
+  fn on_undo(&mut self) {
+    // undo the computer's reply along with your own volley
+    let mut undone = false;
+    while self.game.undo() {
+      undone = true;
+      if self.game.is_user_turn() {
+        break;
+      }
+    }
+    if undone {
+      self.pending_shots.clear();
+      self.duration = None;
+      self.message = "Last volley undone".into();
+    } else if self.game.practice {
+      self.message = "Nothing to undo".into();
+    }
+  }
+
+  /// sweep the region around the cursor for ships, it takes the turn
+  fn on_radar(&mut self) {
+    let (row, col) = self.active();
+    let msg = if self.game.is_won() || !self.is_players_turn() {
+      "Not your turn".into()
+    } else {
+      match Coord::new(row, col).map(|c| self.game.radar(c)) {
+        Some(Ok(msg)) => {
+          self.end_turn();
+          msg
+        }
+        Some(Err(msg)) => msg,
+        None => return,
+      }
+    };
+    self.message = format!(
+      "{}{}{}",
+      self.message,
+      if self.message.is_empty() { "" } else { "\n" },
+      msg
+    );
+  }
+
+  /// describe the cursor cell on both boards, only your own fleet is shown
+  fn on_inspect(&mut self) {
+    let (row, col) = self.active();
+    let c = match Coord::new(row, col) {
+      Some(c) => c,
+      None => return,
+    };
+    let player = self.game.player_by_turn(self.viewer());
+    let tracking = player.opponent_board().cell_info(c);
+    let own = player.player_board().cell_info(c);
+    let [own_name, opponent_name] = self.viewer_names();
+    self.message = format!(
+      "Row {} column {} | {}: {} | {}: {}",
+      row,
+      col,
+      opponent_name,
+      tracking.status.label(self.accessible),
+      own_name,
+      match own.ship {
+        Some(ship) => format!(
+          "{} {} {}/{} afloat",
+          own.status.label(self.accessible),
+          ship.ship_type.name(),
+          ship.cells_afloat,
+          ship.ship_type.cell_count()
+        ),
+        None => own.status.label(self.accessible),
+      }
+    );
+  }
+
+  fn on_replay(&mut self) {
+    self.state = match self.state {
+      AppState::GameOver => AppState::Replay {
+        step: 0,
+        autoplay: false,
+        game: Box::new(self.game.state_at(0)),
+      },
+      AppState::Replay { .. } => AppState::GameOver,
+      AppState::Playing => AppState::Playing,
+      AppState::Tutorial(step) => AppState::Tutorial(step),
+      AppState::HandOff => AppState::HandOff,
+    };
+  }
+
+  /// fresh game with the same rule and settings
+  fn on_play_again(&mut self) {
+    self.game.reset();
+    self.state = AppState::Playing;
+    self.pending_shots.clear();
+    self.maneuver_from = None;
+    self.maneuver_rotation = None;
+    self.hint = None;
+    self.explained_miss = None;
+    self.cursors = [(0, 0); 2];
+    self.message = String::default();
+    self.last_message = None;
+    self.start_time = Instant::now();
+    self.duration = None;
+    self.turn_start = Instant::now();
+    self.turn_durations = vec![];
+  }
+
+  fn on_replay_step(&mut self, forward: bool) {
+    let len = self.game.history().len();
+    if let AppState::Replay { step, game, .. } = &mut self.state {
+      let next = if forward {
+        (*step + 1).min(len)
+      } else {
+        step.saturating_sub(1)
+      };
+      if next != *step {
+        *step = next;
+        **game = self.game.state_at(next);
+      }
+    }
+  }
+
+  fn on_replay_autoplay(&mut self) {
+    if let AppState::Replay { autoplay, .. } = &mut self.state {
+      *autoplay = !*autoplay;
+    }
+  }
+
   fn is_valid_rule(&mut self) -> bool {
-    self.game.is_valid_rule(self.selected_coordinates.len())
+    self.game.is_valid_rule(self.pending_shots.len())
   }
 
-  fn is_selected(&self, coordinate: Coordinate) -> bool {
-    self.selected_coordinates.iter().any(|c| *c == coordinate)
+  fn is_pending(&self, coordinate: Coordinate) -> bool {
+    self.pending_shots.contains(&coordinate)
   }
 
   fn active(&self) -> Coordinate {
-    (self.active_row, self.active_column)
+    self.cursors[self.viewer()]
+  }
+
+  fn cursor_mut(&mut self) -> &mut Coordinate {
+    let viewer = self.viewer();
+    &mut self.cursors[viewer]
+  }
+
+  /// whose boards are shown, the second player's on their hot seat turns
+  fn viewer(&self) -> usize {
+    match self.state {
+      AppState::Playing | AppState::HandOff if self.hot_seat && !self.game.is_user_turn() => 1,
+      _ => 0,
+    }
+  }
+
+  /// whether the player at the keyboard may fire now
+  fn is_players_turn(&self) -> bool {
+    self.hot_seat || self.game.is_user_turn()
+  }
+
+  /// after a volley or radar sweep, hot seat games hide the boards for the hand-off
+  fn end_turn(&mut self) {
+    self.hint = None;
+    self.turn_durations.push(self.turn_start.elapsed());
+    self.autosave();
+    if self.hot_seat && !self.game.is_won() {
+      self.state = AppState::HandOff;
+      self.turn_start = Instant::now();
+    }
+  }
+
+  fn autosave(&mut self) {
+    if let Some(path) = &self.autosave {
+      if let Err(e) = self.game.save_to_path(path) {
+        self.message = format!("Autosave failed: {}", e);
+      }
+    }
+  }
+
+  /// game shown on the boards, the replayed one while in a replay
+  fn board_game(&self) -> &Game {
+    match &self.state {
+      AppState::Replay { game, .. } => game,
+      AppState::Playing | AppState::Tutorial(_) | AppState::GameOver | AppState::HandOff => {
+        &self.game
+      }
+    }
+  }
+
+  /// the next player's name while the boards are hidden between hot seat turns
+  pub fn handing_off_to(&self) -> Option<&str> {
+    match self.state {
+      AppState::HandOff => Some(self.viewer_names()[0]),
+      _ => None,
+    }
+  }
+
+  /// names of the player whose boards are shown and of their opponent
+  pub fn viewer_names(&self) -> [&str; 2] {
+    let viewer = self.viewer();
+    [
+      self.game.player_by_turn(viewer).name(),
+      self.game.player_by_turn(1 - viewer).name(),
+    ]
+  }
+
+  /// volleys fired so far, up to the current step in a replay
+  pub fn history(&self) -> &[Volley] {
+    self.board_game().history()
+  }
+
+  pub fn sinkings(&self) -> &[Sinking] {
+    self.board_game().sinkings()
+  }
+
+  /// the fleet of the player at `turn`, 0 is you
+  pub fn fleet(&self, turn: usize) -> &Board {
+    let game = self.board_game();
+    if turn == 0 {
+      game.player().player_board()
+    } else {
+      game.computer().player_board()
+    }
+  }
+
+  /// what the viewer knows about the opponent's ships, e.g. "Cross: sunk" or "Line #2: damaged"
+  pub fn ship_status(&self) -> Vec<String> {
+    let game = self.board_game();
+    let fleet = game.player_by_turn(1 - self.viewer()).player_board();
+    let mut status = game
+      .player_by_turn(self.viewer())
+      .opponent_board()
+      .firing_status()
+      .iter()
+      .map(|(id, status)| {
+        let ship = fleet.ship_label(id).unwrap_or_else(|| id.clone());
+        format!("{}: {}", ship, status)
+      })
+      .collect::<Vec<_>>();
+    // ids are random, the labels give a steady order
+    status.sort();
+    status
+  }
+
+  pub fn is_replay(&self) -> bool {
+    matches!(self.state, AppState::Replay { .. })
+  }
+
+  /// current step and total volleys while in a replay
+  pub fn replay_progress(&self) -> Option<(usize, usize)> {
+    match self.state {
+      AppState::Replay { step, .. } => Some((step, self.game.history().len())),
+      AppState::Playing | AppState::Tutorial(_) | AppState::GameOver | AppState::HandOff => None,
+    }
+  }
+
+  /// walks a single player game through firing, sinking and the status line
+  pub fn start_tutorial(&mut self) {
+    if let AppState::Playing = self.state {
+      self.state = AppState::Tutorial(TutorialStep::FirstShot);
+    }
+  }
+
+  /// what the tutorial asks the player to do next
+  pub fn tutorial_prompt(&self) -> Option<&'static str> {
+    match self.state {
+      AppState::Tutorial(step) => Some(step.prompt()),
+      _ => None,
+    }
+  }
+
+  pub fn is_game_over(&self) -> bool {
+    matches!(self.state, AppState::GameOver)
+  }
+
+  /// time left on the blitz match clock
+  pub fn clock_left(&self) -> Option<Duration> {
+    self
+      .blitz
+      .map(|blitz| blitz.saturating_sub(self.start_time.elapsed()))
+  }
+
+  pub fn outcome(&self) -> Option<Outcome> {
+    self.game.outcome()
+  }
+
+  pub fn winner(&self) -> Option<usize> {
+    self.game.winner()
+  }
+
+  pub fn winner_name(&self) -> Option<&str> {
+    match self.game.winner()? {
+      0 => Some(self.player_name()),
+      _ => Some(self.computer_name()),
+    }
+  }
+
+  pub fn volley_count(&self) -> usize {
+    self.game.history().len()
+  }
+
+  /// ships lost by the player and the computer
+  pub fn ships_sunk(&self) -> [usize; 2] {
+    self.game.ships_sunk()
+  }
+
+  /// share of shots on a ship, for the player and the computer
+  pub fn accuracy(&self) -> [f64; 2] {
+    [self.game.accuracy(0), self.game.accuracy(1)]
+  }
+
+  /// how the player and the computer fired against the fewest shots for the ships they sank
+  pub fn shot_analysis(&self) -> [ShotAnalysis; 2] {
+    [self.game.shot_analysis(0), self.game.shot_analysis(1)]
+  }
+
+  /// shots fired by the player and the computer over the whole game
+  pub fn total_shots(&self) -> [usize; 2] {
+    [
+      self.game.player().total_shots(),
+      self.game.computer().total_shots(),
+    ]
+  }
+
+  pub fn scores(&self) -> [i32; 2] {
+    [self.game.score(0), self.game.score(1)]
+  }
+
+  /// average time the user took to fire, the computer's turns aren't counted
+  pub fn average_turn_duration(&self) -> Option<Duration> {
+    average_duration(&self.turn_durations)
+  }
+
+  /// code of the computer's fleet to share the same board with others
+  pub fn board_code(&self) -> String {
+    self.game.computer().player_board().to_code()
+  }
+
+  /// write the game in progress so a later run can resume it
+  pub fn save(&self, path: &Path) -> Result<(), GameError> {
+    self.game.save_to_path(path)
+  }
+
+  pub fn player_name(&self) -> &str {
+    self.game.player().name()
+  }
+
+  pub fn computer_name(&self) -> &str {
+    self.game.computer().name()
   }
 
   pub fn rule(&self) -> &Rule {
@@ -130,44 +740,282 @@ impl App {
     self.game.is_won()
   }
 
-  pub fn cell(&self, c: Coordinate, read_only: bool) -> Cell {
+  pub fn is_user_turn(&self) -> bool {
+    self.game.is_user_turn()
+  }
+
+  /// shots that can still be staged this turn
+  pub fn shots_left(&self) -> usize {
+    self.game.shots_left(self.pending_shots.len())
+  }
+
+  /// the missed shot being explained and what the tracking board showed around it
+  pub fn miss_clues(&self) -> Option<(Coord, Vec<(Coord, Clue)>)> {
+    let at = self.explained_miss?;
+    let tracking = self.game.player_by_turn(self.viewer()).opponent_board();
+    Some((at, tracking.clues_around(at)))
+  }
+
+  /// corners of the box around the opponent's last ship, when revealing it
+  pub fn last_ship_region(&self) -> Option<(Coord, Coord)> {
+    if !self.reveal_last_ship || self.is_game_over() {
+      return None;
+    }
+    self
+      .game
+      .player_by_turn(1 - self.viewer())
+      .player_board()
+      .last_ship_region()
+  }
+
+  pub fn is_quit_pending(&self) -> bool {
+    self.pending_quit
+  }
+
+  pub fn is_help_shown(&self) -> bool {
+    self.show_help
+  }
+
+  pub fn is_practice(&self) -> bool {
+    self.game.practice
+  }
+
+  pub fn radar_sweeps_left(&self) -> usize {
+    self.game.player().radar_sweeps_left()
+  }
+
+  pub fn hints_left(&self) -> usize {
+    self.game.player_by_turn(self.viewer()).hints_left()
+  }
+
+  pub fn cell(&self, c: Coord, read_only: bool) -> Cell<'_> {
     Cell::new(self, c, read_only)
   }
 
   pub fn on_key(&mut self, key: Key) {
-    match key {
-      Key::Up | Key::Char('k') => self.on_up(),
-      Key::Down | Key::Char('j') => self.on_down(),
-      Key::Left | Key::Char('h') => self.on_left(),
-      Key::Right | Key::Char('l') => self.on_right(),
-      Key::Char(' ') => self.on_select(),
-      Key::Char('\n') => self.on_fire(),
+    self.handle_key(key);
+    self.remember_message();
+  }
+
+  /// last message with the number of turns since it arrived
+  pub fn last_message(&self) -> Option<(&str, usize)> {
+    self
+      .last_message
+      .as_ref()
+      .map(|(message, turn)| (message.as_str(), self.volley_count().saturating_sub(*turn)))
+  }
+
+  fn remember_message(&mut self) {
+    if !self.message.is_empty() {
+      self.last_message = Some((self.message.clone(), self.volley_count()));
+    }
+  }
+
+  /// a mouse click on a cell, of your own board or the opponent's
+  pub fn on_click(&mut self, button: MouseButton, own_board: bool, at: Coord) {
+    self.handle_click(button, own_board, at);
+    self.remember_message();
+  }
+
+  fn handle_click(&mut self, button: MouseButton, own_board: bool, at: Coord) {
+    // prompts and overlays wait for a key
+    let playing = matches!(self.state, AppState::Playing | AppState::Tutorial(_));
+    if !playing || self.pending_quit || self.show_help || self.explained_miss.is_some() {
+      return;
+    }
+    match (button, own_board) {
+      // stage the cell, the volley goes off once it's full or a staged cell is clicked again
+      (MouseButton::Left, false) => {
+        self.confirming = None;
+        *self.cursor_mut() = at.into();
+        if !self.is_pending(at.into()) {
+          self.on_select();
+          if self.shots_left() > 0 {
+            return;
+          }
+        }
+        self.on_fire();
+      }
+      (MouseButton::Right, true) => {
+        *self.cursor_mut() = at.into();
+        self.on_inspect();
+      }
+      // the opponent's cells hold nothing to inspect that the board doesn't already show
+      _ => {}
+    }
+  }
+
+  fn handle_key(&mut self, key: Key) {
+    let action = self.keymap.action(key);
+    if self.pending_quit {
+      match key {
+        Key::Char('y') => self.should_quit = true,
+        Key::Char('n') | Key::Esc => self.pending_quit = false,
+        _ => { /* keep asking */ }
+      }
+      return;
+    }
+    if action == Some(Action::Quit) {
+      // a finished game has nothing left to lose
+      if self.is_game_over() || self.is_replay() {
+        self.should_quit = true;
+      } else {
+        self.pending_quit = true;
+      }
+      return;
+    }
+    // any key dismisses the help overlay
+    if self.show_help {
+      self.show_help = false;
+      return;
+    }
+    // and the miss explanation
+    if self.explained_miss.take().is_some() {
+      return;
+    }
+    // and shows the boards to the next hot seat player
+    if let AppState::HandOff = self.state {
+      self.state = AppState::Playing;
+      return;
+    }
+    let action = match action {
+      Some(Action::Help) => {
+        self.show_help = true;
+        return;
+      }
+      Some(Action::Palette) => {
+        self.palette = self.palette.next();
+        self.message = format!("Colors: {}", self.palette.name());
+        return;
+      }
+      Some(action) => action,
+      None => return,
+    };
+    match self.state {
+      AppState::Replay { .. } => match action {
+        Action::MoveLeft => self.on_replay_step(false),
+        Action::MoveRight => self.on_replay_step(true),
+        Action::Select => self.on_replay_autoplay(),
+        Action::Replay | Action::Back => self.on_replay(),
+        _ => { /* do nothing */ }
+      },
+      AppState::GameOver => match action {
+        Action::PlayAgain => self.on_play_again(),
+        Action::Replay => self.on_replay(),
+        _ => { /* do nothing */ }
+      },
+      AppState::HandOff => { /* handled above */ }
+      AppState::Playing | AppState::Tutorial(_) => match (self.confirming.take(), action) {
+        // limited abilities only go off once confirmed, any other key drops them
+        (Some((Action::Radar, _)), Action::Fire) => {
+          self.message.clear();
+          self.on_radar();
+        }
+        (_, Action::Radar) => {
+          self.confirming = Some((Action::Radar, CONFIRM_TICKS));
+          self.message = "Radar: press <enter> to sweep around the cursor".into();
+        }
+        (Some((Action::Scuttle, _)), Action::Fire) => self.on_scuttle(),
+        (_, Action::Scuttle) => {
+          self.confirming = Some((Action::Scuttle, CONFIRM_TICKS));
+          self.message = "Scuttle: press <enter> to sink your ship under the cursor".into();
+        }
+        (_, Action::Maneuver) => self.on_maneuver(),
+        (_, Action::Rotate) => self.on_rotate(),
+        (_, Action::Hint) => self.on_hint(),
+        (_, action) => self.on_action(action),
+      },
+    }
+  }
+
+  fn on_action(&mut self, action: Action) {
+    match action {
+      Action::MoveUp => self.on_up(),
+      Action::MoveDown => self.on_down(),
+      Action::MoveLeft => self.on_left(),
+      Action::MoveRight => self.on_right(),
+      Action::Select => self.on_select(),
+      Action::Unselect => self.on_unselect(),
+      Action::Back => self.on_clear_selection(),
+      Action::Fire => self.on_fire(),
+      Action::Undo => self.on_undo(),
+      Action::Inspect => self.on_inspect(),
       _ => { /* do nothing */ }
     }
   }
 
+  /// adaptive waits half again as long as easy, hard twice as long
+  fn bot_delay_ticks(&self) -> u16 {
+    let delay = self.bot_delay;
+    match self.game.difficulty() {
+      Difficulty::Easy => delay,
+      Difficulty::Adaptive => delay.saturating_add(delay / 2),
+      Difficulty::Hard => delay.saturating_mul(2),
+    }
+  }
+
   pub fn on_tick(&mut self) {
+    if self.clock_left() == Some(Duration::ZERO) && !self.is_won() {
+      self.message = match self.game.end_by_clock() {
+        Outcome::Win(_) => format!("Time's up, {} won", self.winner_name().unwrap_or_default()),
+        Outcome::Draw => "Time's up, it's a draw".into(),
+      };
+    }
+    self.confirming = match self.confirming {
+      Some((action, ticks)) if ticks > 1 => Some((action, ticks - 1)),
+      _ => None,
+    };
     if self.is_won() && self.duration.is_none() {
       let duration = self.start_time.elapsed();
       self.duration = Some(duration);
       self.message = format!("{} (In {} seconds)", self.message, duration.as_secs());
+      self.state = AppState::GameOver;
     }
-    // computer delays firing by 2 seconds to make the game feel more natural
-    if !self.game.is_user_turn() && !self.is_won() && self.frame_count % 8 == 0 {
-      self.message = self.game.bot_fire();
+    // the computer always fires on its turn, after a delay to make the game feel more natural
+    if !self.hot_seat && !self.game.is_user_turn() && !self.is_won() {
+      if self.bot_wait >= self.bot_delay_ticks() {
+        self.message = self.game.bot_fire();
+        self.turn_start = Instant::now();
+        self.bot_wait = 0;
+        self.autosave();
+      } else {
+        self.bot_wait += 1;
+      }
+    }
+    // replay autoplay advances one volley per second
+    if let AppState::Replay {
+      step,
+      autoplay: true,
+      ..
+    } = self.state
+    {
+      if step >= self.game.history().len() {
+        self.on_replay_autoplay();
+      } else if self.frame_count.is_multiple_of(4) {
+        self.on_replay_step(true);
+      }
     }
+    self.remember_message();
     self.frame_count += 1;
   }
 }
 
+fn average_duration(durations: &[Duration]) -> Option<Duration> {
+  if durations.is_empty() {
+    None
+  } else {
+    Some(durations.iter().sum::<Duration>() / durations.len() as u32)
+  }
+}
+
 pub struct Cell<'app> {
   app: &'app App,
-  coordinate: Coordinate,
+  coordinate: Coord,
   read_only: bool,
 }
 
 impl<'app> Cell<'app> {
-  fn new(app: &'app App, coordinate: Coordinate, read_only: bool) -> Self {
+  fn new(app: &'app App, coordinate: Coord, read_only: bool) -> Self {
     Self {
       app,
       coordinate,
@@ -176,61 +1024,627 @@ impl<'app> Cell<'app> {
   }
 
   fn get_position_status(&self) -> Status {
-    let (pos, ship) = if self.read_only {
-      self
+    // nothing shows while the keyboard changes hands
+    if self.app.handing_off_to().is_some() {
+      return Status::Space;
+    }
+    let viewer = self.app.viewer();
+    let player = self.app.board_game().player_by_turn(viewer);
+    let board = if self.read_only {
+      player.player_board()
+    } else {
+      player.opponent_board()
+    };
+
+    let status = board.cell_info(self.coordinate).status;
+    // once the game is over the rest of the computer's fleet is revealed
+    if !self.read_only && status == Status::Space && self.app.is_game_over() {
+      return self
         .app
         .game
-        .player()
+        .player_by_turn(1 - viewer)
         .player_board()
-        .find_position_and_ship(self.coordinate)
-    } else {
-      self
+        .cell_info(self.coordinate)
+        .status;
+    }
+    status
+  }
+
+  fn is_active(&self) -> bool {
+    !self.read_only
+      && !self.app.is_replay()
+      && self.app.handing_off_to().is_none()
+      && self.app.active() == self.coordinate.into()
+  }
+
+  /// an opponent ship not fired at yet, faintly shown during the tutorial
+  fn is_ghost(&self) -> bool {
+    !self.read_only
+      && self.app.tutorial_prompt().is_some()
+      && self.get_position_status() == Status::Space
+      && self
         .app
         .game
-        .player()
-        .opponent_board()
-        .find_position_and_ship(self.coordinate)
-    };
+        .player_by_turn(1 - self.app.viewer())
+        .player_board()
+        .cell_info(self.coordinate)
+        .status
+        == Status::Live
+  }
 
-    pos.get_status(ship)
+  /// on your own board, whether the ship being moved fits if this cell is part of its preview
+  fn preview(&self) -> Option<bool> {
+    if !self.read_only || self.app.is_replay() {
+      return None;
+    }
+    let (cells, fits) = self.app.maneuver_preview()?;
+    cells
+      .contains(&Coordinate::from(self.coordinate))
+      .then_some(fits)
   }
 
-  fn is_active(&self) -> bool {
-    !self.read_only && self.app.active() == self.coordinate
-  }
-
-  fn is_selected(&self) -> bool {
-    !self.read_only && self.app.is_selected(self.coordinate)
-  }
-
-  pub fn block(&self) -> Block {
-    Block::default()
-      .borders(Borders::ALL)
-      .style(Style::default().bg(Color::Black).fg(
-        // cell  border color
-        if self.is_selected() {
-          Color::Yellow
-        } else if self.is_active() {
-          Color::Cyan
-        } else {
-          match self.get_position_status() {
-            Status::Live => Color::Yellow,
-            Status::Hit | Status::Kill => Color::Red,
-            Status::Miss | Status::Space => Color::White,
-          }
-        },
-      ))
-      .border_type(BorderType::Rounded)
+  fn in_region(&self) -> bool {
+    if self.read_only || self.app.is_replay() {
+      return false;
+    }
+    let (row, col) = self.coordinate.into();
+    self.app.last_ship_region().is_some_and(|(from, to)| {
+      let ((top, left), (bottom, right)) = (Coordinate::from(from), Coordinate::from(to));
+      (top..=bottom).contains(&row) && (left..=right).contains(&col)
+    })
+  }
+
+  fn is_hinted(&self) -> bool {
+    !self.read_only && !self.app.is_replay() && self.app.hint == Some(self.coordinate)
   }
 
-  pub fn text_style(&self) -> Style {
-    // cell background color
-    Style::default().bg(Color::Black)
+  fn is_pending(&self) -> bool {
+    !self.read_only && !self.app.is_replay() && self.app.is_pending(self.coordinate.into())
+  }
+
+  pub fn view(&self) -> CellView {
+    CellView {
+      status: self.get_position_status(),
+      active: self.is_active(),
+      pending: self.is_pending(),
+      hinted: self.is_hinted(),
+      ghost: self.is_ghost(),
+      preview: self.preview(),
+      region: self.in_region(),
+    }
   }
 }
 
-impl fmt::Display for Cell<'_> {
-  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(f, "{}", self.get_position_status())
+#[cfg(test)]
+mod tests {
+  use super::{super::game::ShipType, *};
+
+  #[test]
+  fn test_app_pending_shots_fury_cap() {
+    let mut app = App::new("test".into(), Game::new(Rule::Fury, Difficulty::Easy));
+
+    // fury allows one shot per ship alive
+    for _ in 0..5 {
+      app.on_key(Key::Char(' '));
+      app.on_key(Key::Right);
+    }
+    assert_eq!(app.pending_shots.len(), 4);
+    assert_eq!(app.message, "Maximum shots for rule selected");
+
+    // unselecting a pending shot frees up a slot
+    app.on_key(Key::Left);
+    app.on_key(Key::Left);
+    app.on_key(Key::Char(' '));
+    assert_eq!(app.pending_shots.len(), 3);
+    assert!(!app.is_pending((0, 3)));
+
+    app.on_key(Key::Char('\n'));
+    assert!(app.pending_shots.is_empty());
+    assert!(!app.game.is_user_turn());
+  }
+
+  #[test]
+  fn test_app_clear_pending_shots() {
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Fury, Difficulty::Easy, 1),
+    );
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    while !app.game.is_user_turn() {
+      app.game.bot_fire();
+    }
+    let history = app.game.history().to_vec();
+
+    for _ in 0..3 {
+      app.on_key(Key::Down);
+      app.on_key(Key::Char(' '));
+    }
+    assert_eq!(app.pending_shots, vec![(1, 0), (2, 0), (3, 0)]);
+
+    app.on_key(Key::Backspace);
+    assert_eq!(app.pending_shots, vec![(1, 0), (2, 0)]);
+
+    app.on_key(Key::Esc);
+    assert!(app.pending_shots.is_empty());
+    assert!(!(0..ROWS)
+      .flat_map(|r| (0..COLS).filter_map(move |c| Coord::new(r, c)))
+      .any(|c| app.cell(c, false).view().pending));
+    assert_eq!(app.game.history(), &history[..]);
+    assert!(app.game.is_user_turn());
+  }
+
+  #[test]
+  fn test_app_replay_steps_clamp() {
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Fury, Difficulty::Easy, 1),
+    );
+    // replay is only available once the game is over
+    app.on_key(Key::Char('v'));
+    assert!(!app.is_replay());
+
+    while !app.game.is_won() {
+      app.game.bot_fire();
+    }
+    app.on_tick();
+    let len = app.game.history().len();
+
+    app.on_key(Key::Char('v'));
+    assert_eq!(app.replay_progress(), Some((0, len)));
+    app.on_key(Key::Left);
+    assert_eq!(app.replay_progress(), Some((0, len)));
+
+    for _ in 0..len + 3 {
+      app.on_key(Key::Right);
+    }
+    assert_eq!(app.replay_progress(), Some((len, len)));
+    assert!(app.board_game().player().player_board() == app.game.player().player_board());
+
+    app.on_key(Key::Esc);
+    assert!(!app.is_replay());
+    assert!(app.is_game_over());
+  }
+
+  #[test]
+  fn test_app_play_again() {
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Fury, Difficulty::Easy, 1),
+    );
+    app.on_key(Key::Right);
+    while !app.game.is_won() {
+      app.game.bot_fire();
+    }
+    app.on_tick();
+    assert!(app.is_game_over());
+    assert!(app.winner_name().is_some());
+
+    // cursor keys do nothing on the result screen
+    app.on_key(Key::Char(' '));
+    assert!(app.pending_shots.is_empty());
+
+    app.on_key(Key::Char('r'));
+    assert!(!app.is_game_over());
+    assert!(!app.is_won());
+    assert!(app.game.is_user_turn());
+    assert_eq!(app.volley_count(), 0);
+    assert_eq!(app.ships_sunk(), [0, 0]);
+    assert_eq!(app.active(), (0, 0));
+    // fury shots follow the player's fleet, all of it afloat again
+    assert_eq!(app.shots_left(), 4);
+  }
+
+  #[test]
+  fn test_app_ship_status_uniform_fleet() {
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Easy, 1);
+    game.set_fleet(ShipType::I.uniform_fleet()).unwrap();
+    let mut app = App::new("test".into(), game);
+    assert!(app.ship_status().is_empty());
+
+    // every ship hit gets its own line
+    let shots = app.game.computer().player_board().ship_cells()[..]
+      .iter()
+      .step_by(ShipType::I.cell_count())
+      .filter_map(|c| Coord::try_from(*c).ok())
+      .collect();
+    app.game.fire(&shots, false);
+    assert_eq!(
+      app.ship_status(),
+      [
+        "Line #1: damaged",
+        "Line #2: damaged",
+        "Line #3: damaged",
+        "Line #4: damaged"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_average_duration() {
+    assert_eq!(average_duration(&[]), None);
+    assert_eq!(
+      average_duration(&[
+        Duration::from_secs(2),
+        Duration::from_secs(3),
+        Duration::from_millis(5500),
+      ]),
+      Some(Duration::from_millis(3500))
+    );
+  }
+
+  #[test]
+  fn test_app_custom_keymap() {
+    let mut app = App::new("test".into(), Game::new(Rule::Default, Difficulty::Easy));
+    app.keymap.bind(Key::Char('f'), Action::Fire);
+
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('f'));
+    assert!(app.pending_shots.is_empty());
+    assert!(!app.game.is_user_turn());
+    assert_eq!(app.volley_count(), 1);
+
+    // unmapped keys are ignored
+    app.on_key(Key::Char('z'));
+    assert!(!app.should_quit);
+    app.on_key(Key::Ctrl('c'));
+    assert!(!app.should_quit);
+    app.on_key(Key::Char('y'));
+    assert!(app.should_quit);
+  }
+
+  #[test]
+  fn test_app_confirm_quit() {
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Default, Difficulty::Easy, 1),
+    );
+    app.on_key(Key::Char('q'));
+    assert!(app.is_quit_pending());
+    // other keys don't answer the prompt
+    app.on_key(Key::Char(' '));
+    assert!(app.pending_shots.is_empty());
+    app.on_key(Key::Char('n'));
+    assert!(!app.is_quit_pending());
+    assert!(!app.should_quit);
+
+    app.on_key(Key::Char('q'));
+    app.on_key(Key::Esc);
+    assert!(!app.should_quit);
+    app.on_key(Key::Char('q'));
+    app.on_key(Key::Char('y'));
+    assert!(app.should_quit);
+  }
+
+  #[test]
+  fn test_app_inspect() {
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Default, Difficulty::Easy, 1),
+    );
+    let cell = app.game.player().player_board().ship_cells()[0];
+    for _ in 0..cell.0 {
+      app.on_key(Key::Down);
+    }
+    for _ in 0..cell.1 {
+      app.on_key(Key::Right);
+    }
+    app.on_key(Key::Char('i'));
+    assert!(app
+      .message
+      .starts_with(&format!("Row {} column {} | Computer:", cell.0, cell.1)));
+    assert!(app.message.contains("afloat"));
+
+    app.accessible = true;
+    app.on_key(Key::Char('i'));
+    assert!(app.message.contains("Computer: water | You: ship"));
+  }
+
+  #[test]
+  fn test_app_click() {
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Default, Difficulty::Easy, 1),
+    );
+    let at = Coord::new(4, 6).unwrap();
+    // right clicks only inspect your own board
+    app.on_click(MouseButton::Right, false, at);
+    assert!(app.message.is_empty());
+    assert_eq!(app.active(), (0, 0));
+    app.on_click(MouseButton::Right, true, at);
+    assert!(app.message.starts_with("Row 4 column 6"));
+    assert_eq!(app.active(), (4, 6));
+
+    // left clicks fire at the opponent once the volley is full, one shot by default
+    app.message.clear();
+    app.on_click(MouseButton::Left, true, at);
+    assert!(app.pending_shots.is_empty());
+    app.on_click(MouseButton::Left, false, at);
+    assert_eq!(app.game.history().len(), 1);
+    assert!(app.game.history()[0].shots.contains(&at));
+  }
+
+  #[test]
+  fn test_app_last_message() {
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Default, Difficulty::Easy, 1),
+    );
+    app.bot_delay = 0;
+    assert_eq!(app.last_message(), None);
+
+    app.on_key(Key::Char('i'));
+    let inspected = app.message.clone();
+    assert_eq!(app.last_message(), Some((inspected.as_str(), 0)));
+
+    // the alert fading out or keys without a message keep it
+    app.message.clear();
+    app.on_key(Key::Right);
+    app.on_tick();
+    assert_eq!(app.last_message(), Some((inspected.as_str(), 0)));
+
+    // the bot's reply replaces it, and ages as the game goes on
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    app.message.clear();
+    app.on_tick();
+    let reply = app.message.clone();
+    assert!(!reply.is_empty());
+    assert_eq!(app.last_message(), Some((reply.as_str(), 0)));
+    app.message.clear();
+    app.game.fire(
+      &[Coord::try_from((5, 5)).unwrap()].iter().copied().collect(),
+      false,
+    );
+    app.game.bot_fire();
+    assert_eq!(app.last_message(), Some((reply.as_str(), 2)));
+  }
+
+  #[test]
+  fn test_app_hint() {
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Default, Difficulty::Easy, 1),
+    );
+    app.on_key(Key::Char('n'));
+    assert!(app.message.starts_with("Hint: try "), "{}", app.message);
+    let hint = app.hint.unwrap();
+    assert!(app.cell(hint, false).view().hinted);
+    assert!(!app.cell(hint, true).view().hinted);
+    assert_eq!(app.hints_left(), 2);
+
+    // gone once the turn ends
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    assert_eq!(app.hint, None);
+  }
+
+  #[test]
+  fn test_app_tutorial() {
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Default, Difficulty::Easy, 1),
+    );
+    app.start_tutorial();
+    assert_eq!(
+      app.tutorial_prompt(),
+      Some(TutorialStep::FirstShot.prompt())
+    );
+    let ghosts = (0..ROWS)
+      .flat_map(|r| (0..COLS).filter_map(move |c| Coord::new(r, c)))
+      .filter(|c| app.cell(*c, false).view().ghost)
+      .count();
+    assert_eq!(
+      ghosts,
+      app.game.computer().player_board().ship_cells().len()
+    );
+
+    // nothing to fire yet
+    app.on_key(Key::Char('\n'));
+    assert_eq!(
+      app.tutorial_prompt(),
+      Some(TutorialStep::FirstShot.prompt())
+    );
+
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    assert_eq!(app.history().len(), 1);
+    assert_eq!(app.tutorial_prompt(), Some(TutorialStep::SinkShip.prompt()));
+  }
+
+  #[test]
+  fn test_app_blitz() {
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Fury, Difficulty::Easy, 1),
+    );
+    app.blitz = Some(Duration::from_secs(60));
+    app.on_tick();
+    assert!(!app.is_won());
+
+    // a draw when nobody sank anything
+    app.blitz = Some(Duration::ZERO);
+    app.on_tick();
+    assert_eq!(app.outcome(), Some(Outcome::Draw));
+    assert!(app.is_game_over());
+
+    // otherwise whoever sank more ships
+    app.on_play_again();
+    // every cell of one ship, fleets hold a single ship of each type
+    let board = app.game.computer().player_board();
+    let ship_type = |c: &Coord| board.cell_info(*c).ship.map(|s| s.ship_type);
+    let cells = board
+      .ship_cells()
+      .into_iter()
+      .filter_map(|c| Coord::try_from(c).ok())
+      .collect::<Vec<_>>();
+    let cells = cells
+      .iter()
+      .filter(|c| ship_type(c) == ship_type(&cells[0]))
+      .copied()
+      .collect();
+    app.game.fire(&cells, false);
+    assert_eq!(app.game.ships_sunk(), [0, 1]);
+    app.on_tick();
+    assert_eq!(app.outcome(), Some(Outcome::Win(0)));
+    assert!(app.message.starts_with("Time's up"));
+  }
+
+  #[test]
+  fn test_app_bot_fires_on_tick() {
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Default, Difficulty::Easy, 1),
+    );
+    app.bot_delay = 0;
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    assert!(!app.is_user_turn());
+
+    app.on_tick();
+    assert!(app.is_user_turn());
+    assert_eq!(app.volley_count(), 2);
+
+    // with a delay the computer waits that many ticks first
+    app.bot_delay = 2;
+    app.on_key(Key::Down);
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    app.on_tick();
+    app.on_tick();
+    assert!(!app.is_user_turn());
+    app.on_tick();
+    assert!(app.is_user_turn());
+  }
+
+  #[test]
+  fn test_app_bot_delay_by_difficulty() {
+    let ticks = |difficulty| {
+      let mut app = App::new("test".into(), Game::with_seed(Rule::Default, difficulty, 1));
+      app.bot_delay = 2;
+      app.on_key(Key::Char(' '));
+      app.on_key(Key::Char('\n'));
+      let mut ticks = 0;
+      while !app.is_user_turn() {
+        app.on_tick();
+        ticks += 1;
+        // keys still go through while the computer thinks
+        app.on_key(Key::Down);
+      }
+      ticks
+    };
+    assert_eq!(ticks(Difficulty::Easy), 3);
+    assert_eq!(ticks(Difficulty::Adaptive), 4);
+    assert_eq!(ticks(Difficulty::Hard), 5);
+  }
+
+  #[test]
+  fn test_app_radar_needs_confirmation() {
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Default, Difficulty::Easy, 1),
+    );
+    app.bot_delay = 100;
+    let sweeps = app.radar_sweeps_left();
+    app.on_key(Key::Char('s'));
+    assert!(app.is_user_turn());
+    assert_eq!(app.radar_sweeps_left(), sweeps);
+
+    // any other key drops it
+    app.on_key(Key::Down);
+    app.on_key(Key::Char('\n'));
+    assert!(app.is_user_turn());
+    assert_eq!(app.radar_sweeps_left(), sweeps);
+
+    // and so does waiting too long
+    app.on_key(Key::Char('s'));
+    (0..CONFIRM_TICKS).for_each(|_| app.on_tick());
+    app.on_key(Key::Char('\n'));
+    assert_eq!(app.radar_sweeps_left(), sweeps);
+
+    app.on_key(Key::Char('s'));
+    app.on_tick();
+    app.on_key(Key::Char('\n'));
+    assert!(!app.is_user_turn());
+    assert_eq!(app.radar_sweeps_left(), sweeps - 1);
+    assert!(app.message.starts_with("Radar: "));
+  }
+
+  #[test]
+  fn test_app_autosave() {
+    let path = std::env::temp_dir().join(format!("battleship-{}.autosave", std::process::id()));
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Default, Difficulty::Easy, 1),
+    );
+    app.bot_delay = 0;
+    app.autosave = Some(path.clone());
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    app.on_tick();
+    assert_eq!(app.volley_count(), 2);
+
+    let saved = Game::load_from_path(&path).unwrap();
+    assert_eq!(saved.history().len(), 2);
+    let _ = std::fs::remove_file(&path);
+  }
+
+  #[test]
+  fn test_app_reveal_on_game_over() {
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Fury, Difficulty::Easy, 1),
+    );
+    let revealed = |app: &App| {
+      (0..ROWS)
+        .flat_map(|r| (0..COLS).filter_map(move |c| Coord::new(r, c)))
+        .filter(|c| app.cell(*c, false).view().status == Status::Live)
+        .map(Coordinate::from)
+        .collect::<Vec<_>>()
+    };
+    assert!(revealed(&app).is_empty());
+
+    // you only ever fire at water, so the computer wins
+    let ships = app.game.computer().player_board().ship_cells();
+    let water = (0..ROWS)
+      .flat_map(|r| (0..COLS).filter_map(move |c| Coord::new(r, c)))
+      .find(|c| !ships.contains(&Coordinate::from(*c)))
+      .unwrap();
+    while !app.is_won() {
+      if app.game.is_user_turn() {
+        app.game.fire(&std::iter::once(water).collect(), false);
+      } else {
+        app.game.bot_fire();
+      }
+      assert!(revealed(&app).is_empty());
+    }
+    app.on_tick();
+    assert!(app.is_game_over());
+    // the computer won, so some of its fleet was never found
+    assert_eq!(app.game.winner(), Some(1));
+
+    let board = app.game.computer().player_board();
+    let afloat = board
+      .ship_cells()
+      .into_iter()
+      .filter(|c| board.cell_info(Coord::try_from(*c).unwrap()).status == Status::Live)
+      .collect::<Vec<_>>();
+    assert!(!afloat.is_empty());
+    assert_eq!(revealed(&app), afloat);
+  }
+
+  #[test]
+  fn test_app_help_toggle() {
+    let mut app = App::new("test".into(), Game::new(Rule::Default, Difficulty::Easy));
+    app.on_key(Key::Char('?'));
+    assert!(app.is_help_shown());
+
+    // dismissing doesn't move the cursor
+    app.on_key(Key::Right);
+    assert!(!app.is_help_shown());
+    assert_eq!(app.active(), (0, 0));
+
+    app.on_key(Key::Right);
+    assert_eq!(app.active(), (0, 1));
   }
 }
@@ -0,0 +1,111 @@
+use std::{
+  fmt::{self, Display},
+  str::FromStr,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// a calendar day in UTC, counted from 1970-01-01
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub struct Day(i64);
+
+impl Day {
+  pub fn today() -> Self {
+    let seconds = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map_or(0, |d| d.as_secs());
+    Day((seconds / SECONDS_PER_DAY) as i64)
+  }
+
+  pub fn next(self) -> Self {
+    Day(self.0 + 1)
+  }
+
+  /// the same for everyone on this day, FNV-1a of the date so it doesn't change between builds
+  pub fn seed(&self) -> u64 {
+    self
+      .to_string()
+      .bytes()
+      .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+      })
+  }
+
+  /// year, month and day, after Howard Hinnant's `civil_from_days`
+  fn date(&self) -> (i64, i64, i64) {
+    let z = self.0 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+  }
+
+  /// the inverse of `date`, after `days_from_civil`
+  fn from_date(year: i64, month: i64, day: i64) -> Self {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Day(era * 146_097 + doe - 719_468)
+  }
+}
+
+/// like 2026-10-16
+impl Display for Day {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let (year, month, day) = self.date();
+    write!(f, "{:04}-{:02}-{:02}", year, month, day)
+  }
+}
+
+impl FromStr for Day {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid = || format!("invalid date {:?}, expected e.g. 2026-10-16", s);
+    let parts = s
+      .splitn(3, '-')
+      .map(|part| part.parse::<i64>().map_err(|_| invalid()))
+      .collect::<Result<Vec<_>, _>>()?;
+    match parts[..] {
+      [year, month, day] if (1..=12).contains(&month) && (1..=31).contains(&day) => {
+        let parsed = Day::from_date(year, month, day);
+        // days past the end of the month roll over, like 02-30
+        if parsed.date() == (year, month, day) {
+          Ok(parsed)
+        } else {
+          Err(invalid())
+        }
+      }
+      _ => Err(invalid()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_day_seed() {
+    let day = "2026-10-16".parse::<Day>().unwrap();
+    assert_eq!(day.to_string(), "2026-10-16");
+    assert_eq!(day.seed(), 4019525590756294245);
+    assert_eq!(day.next().to_string(), "2026-10-17");
+    assert_ne!(day.next().seed(), day.seed());
+    assert_eq!("1970-01-01".parse(), Ok(Day(0)));
+    assert_eq!(
+      "2024-02-29".parse::<Day>().unwrap().next().to_string(),
+      "2024-03-01"
+    );
+    assert!("2026-02-30".parse::<Day>().is_err());
+    assert!("yesterday".parse::<Day>().is_err());
+  }
+}
@@ -0,0 +1,86 @@
+use std::{
+  error::Error,
+  fmt::{self, Display},
+  io,
+};
+
+/// everything that can stop the game, `main` reports it and exits
+#[derive(Debug)]
+pub enum GameError {
+  Io(io::Error),
+  /// setting up or drawing to the terminal failed
+  Terminal(io::Error),
+  /// a fleet that can't be placed or an invalid board code
+  Placement(String),
+  SaveLoad(String),
+  /// the state socket couldn't be opened or a subscriber couldn't be written to
+  Network(io::Error),
+  /// an invalid key bindings file, or an option the chosen settings don't take
+  Config(String),
+  /// an invalid profile file
+  Profile(String),
+  /// a tracking board file that doesn't fit the game
+  Tracking(String),
+  /// game options that contradict each other, like a win threshold above the fleet size
+  Rule(String),
+}
+
+impl Display for GameError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      GameError::Io(e) => write!(f, "i/o error: {}", e),
+      GameError::Terminal(e) => write!(f, "can't use the terminal: {}", e),
+      GameError::Placement(msg) => write!(f, "invalid fleet: {}", msg),
+      GameError::SaveLoad(msg) => write!(f, "save file error: {}", msg),
+      GameError::Network(e) => write!(f, "state socket error: {}", e),
+      GameError::Config(msg) => write!(f, "invalid configuration: {}", msg),
+      GameError::Profile(msg) => write!(f, "invalid profile: {}", msg),
+      GameError::Tracking(msg) => write!(f, "invalid tracking board: {}", msg),
+      GameError::Rule(msg) => write!(f, "invalid rules: {}", msg),
+    }
+  }
+}
+
+impl Error for GameError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      GameError::Io(e) | GameError::Terminal(e) | GameError::Network(e) => Some(e),
+      _ => None,
+    }
+  }
+}
+
+impl From<io::Error> for GameError {
+  fn from(e: io::Error) -> Self {
+    GameError::Io(e)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::BTreeSet;
+
+  use super::*;
+
+  #[test]
+  fn test_game_error_display() {
+    let io_error = || io::Error::other("");
+    let errors = [
+      GameError::Io(io_error()),
+      GameError::Terminal(io_error()),
+      GameError::Placement(String::default()),
+      GameError::SaveLoad(String::default()),
+      GameError::Network(io_error()),
+      GameError::Config(String::default()),
+      GameError::Profile(String::default()),
+      GameError::Tracking(String::default()),
+      GameError::Rule(String::default()),
+    ];
+    let messages = errors
+      .iter()
+      .map(|e| e.to_string())
+      .collect::<BTreeSet<_>>();
+    assert_eq!(messages.len(), errors.len());
+    assert!(messages.iter().all(|m| !m.trim().is_empty()));
+  }
+}
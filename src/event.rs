@@ -1,32 +1,54 @@
 use std::{io, sync::mpsc, thread, time::Duration};
 
-use termion::{event::Key, input::TermRead};
+use termion::{
+  event::{Event as TermEvent, Key, MouseEvent},
+  input::TermRead,
+};
 
 pub enum Event<I> {
   Input(I),
+  /// a mouse press, release or drag, at 1 based terminal coordinates
+  Mouse(MouseEvent),
   Tick,
+  /// input is closed and no more events will arrive
+  Disconnect,
 }
 
 /// A small event handler that wrap termion input and tick events. Each event
-/// type is handled in its own thread and returned to a common `Receiver`
+/// type is handled in its own thread and returned to a common `Receiver`, so
+/// keys arrive as soon as they're read instead of on the next tick
 pub struct Events {
   rx: mpsc::Receiver<Event<Key>>,
 }
 
 impl Events {
   pub fn new(tick_rate: Duration) -> Events {
+    Self::with_input(io::stdin().events().flatten(), tick_rate)
+  }
+
+  /// events from any source of terminal input, the end of the input is a disconnect
+  fn with_input<I: Iterator<Item = TermEvent> + Send + 'static>(
+    input: I,
+    tick_rate: Duration,
+  ) -> Events {
     let (tx, rx) = mpsc::channel();
 
     let tx_clone = tx.clone();
 
     thread::spawn(move || {
-      let stdin = io::stdin();
-      for key in stdin.keys().flatten() {
-        if let Err(err) = tx_clone.send(Event::Input(key)) {
+      for event in input {
+        let event = match event {
+          TermEvent::Key(key) => Event::Input(key),
+          TermEvent::Mouse(mouse) => Event::Mouse(mouse),
+          TermEvent::Unsupported(_) => continue,
+        };
+        if let Err(err) = tx_clone.send(event) {
           eprintln!("{}", err);
           return;
         }
       }
+      // input reached EOF
+      let _ = tx_clone.send(Event::Disconnect);
     });
 
     thread::spawn(move || loop {
@@ -40,7 +62,47 @@ impl Events {
     Events { rx }
   }
 
-  pub fn next(&self) -> Result<Event<Key>, mpsc::RecvError> {
-    self.rx.recv()
+  /// next event, a closed channel is reported as `Event::Disconnect`
+  pub fn next(&self) -> Event<Key> {
+    self.rx.recv().unwrap_or(Event::Disconnect)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_events_next_disconnect() {
+    let (tx, rx) = mpsc::channel();
+    let events = Events { rx };
+
+    tx.send(Event::Tick).unwrap();
+    assert!(matches!(events.next(), Event::Tick));
+
+    drop(tx);
+    assert!(matches!(events.next(), Event::Disconnect));
+    assert!(matches!(events.next(), Event::Disconnect));
+  }
+
+  #[test]
+  fn test_events_input_between_ticks() {
+    let input = vec![
+      TermEvent::Key(Key::Char('a')),
+      TermEvent::Unsupported(vec![]),
+      TermEvent::Mouse(MouseEvent::Release(3, 4)),
+    ];
+    let events = Events::with_input(input.into_iter(), Duration::from_secs(60));
+    let (mut keys, mut clicks) = (vec![], vec![]);
+    // the first tick fires right away, the next not for a minute
+    for _ in 0..4 {
+      match events.rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+        Event::Input(key) => keys.push(key),
+        Event::Mouse(mouse) => clicks.push(mouse),
+        Event::Tick | Event::Disconnect => {}
+      }
+    }
+    assert_eq!(keys, [Key::Char('a')]);
+    assert_eq!(clicks, [MouseEvent::Release(3, 4)]);
   }
 }
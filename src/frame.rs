@@ -0,0 +1,114 @@
+use std::io::{self, Read};
+
+/// bytes of the length in front of every frame
+const LENGTH_BYTES: usize = 4;
+/// longest frame a reader accepts, anything bigger is a broken stream
+const MAX_FRAME: usize = 1024 * 1024;
+
+/// the message after its length as a little endian u32
+pub fn encode_frame(message: &[u8]) -> Vec<u8> {
+  let mut frame = (message.len() as u32).to_le_bytes().to_vec();
+  frame.extend_from_slice(message);
+  frame
+}
+
+/// reads whole frames from a stream that may hand them over in pieces
+pub struct FrameReader<R> {
+  reader: R,
+  /// bytes read past the last whole frame
+  buffer: Vec<u8>,
+}
+
+impl<R: Read> FrameReader<R> {
+  pub fn new(reader: R) -> Self {
+    Self {
+      reader,
+      buffer: vec![],
+    }
+  }
+
+  /// the next message, reading as often as it takes, none when the stream ends between frames
+  pub fn read_frame(&mut self) -> io::Result<Option<Vec<u8>>> {
+    loop {
+      if let Some(message) = self.take_frame()? {
+        return Ok(Some(message));
+      }
+      let mut chunk = [0; 4096];
+      match self.reader.read(&mut chunk) {
+        Ok(0) if self.buffer.is_empty() => return Ok(None),
+        Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+        Ok(n) => self.buffer.extend_from_slice(&chunk[..n]),
+        Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+        Err(e) => return Err(e),
+      }
+    }
+  }
+
+  fn take_frame(&mut self) -> io::Result<Option<Vec<u8>>> {
+    if self.buffer.len() < LENGTH_BYTES {
+      return Ok(None);
+    }
+    let mut length = [0; LENGTH_BYTES];
+    length.copy_from_slice(&self.buffer[..LENGTH_BYTES]);
+    let length = u32::from_le_bytes(length) as usize;
+    if length > MAX_FRAME {
+      return Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("frame of {} bytes is too long", length),
+      ));
+    }
+    if self.buffer.len() < LENGTH_BYTES + length {
+      return Ok(None);
+    }
+    let message = self.buffer[LENGTH_BYTES..LENGTH_BYTES + length].to_vec();
+    self.buffer.drain(..LENGTH_BYTES + length);
+    Ok(Some(message))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// hands out the given pieces one read at a time
+  struct Pieces(Vec<Vec<u8>>);
+
+  impl Read for Pieces {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+      if self.0.is_empty() {
+        return Ok(0);
+      }
+      let piece = self.0.remove(0);
+      buf[..piece.len()].copy_from_slice(&piece);
+      Ok(piece.len())
+    }
+  }
+
+  #[test]
+  fn test_frame_reader_split_reads() {
+    // a message split across two reads comes back whole
+    let frame = encode_frame(b"{\"volleys\":3}");
+    let (first, second) = frame.split_at(6);
+    let mut reader = FrameReader::new(Pieces(vec![first.to_vec(), second.to_vec()]));
+    assert_eq!(
+      reader.read_frame().unwrap(),
+      Some(b"{\"volleys\":3}".to_vec())
+    );
+    assert_eq!(reader.read_frame().unwrap(), None);
+
+    // even inside the length, and two frames in one read come out one by one
+    let mut both = encode_frame(b"one");
+    both.extend(encode_frame(b""));
+    let mut reader = FrameReader::new(Pieces(vec![both[..2].to_vec(), both[2..].to_vec()]));
+    assert_eq!(reader.read_frame().unwrap(), Some(b"one".to_vec()));
+    assert_eq!(reader.read_frame().unwrap(), Some(vec![]));
+    assert_eq!(reader.read_frame().unwrap(), None);
+
+    // a stream that ends inside a frame is an error
+    let mut reader = FrameReader::new(Pieces(vec![frame[..6].to_vec()]));
+    assert_eq!(
+      reader.read_frame().unwrap_err().kind(),
+      io::ErrorKind::UnexpectedEof
+    );
+  }
+}
@@ -1,851 +1,5160 @@
 use std::{
   collections::{BTreeMap, BTreeSet},
+  convert::TryFrom,
   fmt::{self, Display},
+  fs,
+  path::Path,
+  str::FromStr,
 };
 
-use rand::{prelude::ThreadRng, seq::SliceRandom, Rng};
+use bincode::Options;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use structopt::clap::arg_enum;
-use uuid::Uuid;
+use uuid::{Builder, Variant, Version};
+
+use super::error::GameError;
 
 pub const ROWS: usize = 10;
 pub const COLS: usize = 10;
 const SHIP_SIZE: usize = 3;
-const POS_ADDITION: [i32; 5] = [-2, -1, 0, 1, 2];
+const BARRAGE_SHOTS: usize = 3;
+/// cells the hard computer looks at around a previous hit, in each direction
+const JITTER: usize = 2;
 const ROTATIONS: [u16; 4] = [90, 180, 270, 360];
+/// bumped whenever the save file format changes
+const SAVE_VERSION: u32 = 13;
+/// points per cell of a sunk ship
+const SHIP_CELL_POINTS: i32 = 10;
+/// points lost per shot fired more than one cell away from every ship
+const WASTED_SHOT_PENALTY: i32 = 1;
+/// radar sweeps each player gets per game
+const RADAR_SWEEPS: usize = 2;
+/// suggested shots each player gets per game
+const HINTS: usize = 3;
 
 pub type Coordinate = (usize, usize);
+/// where a ship's shape box starts, negative when the box sticks out over the top or left edge
+type Anchor = (isize, isize);
 type ShipShape = [[Status; SHIP_SIZE]; SHIP_SIZE];
-type FiringResponse = BTreeMap<Coordinate, Status>;
+type FiringResponse = BTreeMap<Coordinate, ShotResult>;
+
+/// outcome of a shot and the ship it landed on, if any
+#[derive(PartialEq, Debug, Clone)]
+struct ShotResult {
+  status: Status,
+  ship: Option<(String, ShipType)>,
+}
+
+/// a coordinate checked to be on the board, (row, col) tuples convert to and from it
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub struct Coord {
+  row: usize,
+  col: usize,
+}
+
+impl Coord {
+  pub fn new(row: usize, col: usize) -> Option<Self> {
+    if row < ROWS && col < COLS {
+      Some(Self { row, col })
+    } else {
+      None
+    }
+  }
+}
+
+/// row letter and column number, e.g. "D4"
+impl Display for Coord {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}{}", (b'A' + self.row as u8) as char, self.col + 1)
+  }
+}
+
+/// a cell like "C7" or "c7", as `Coord` displays it
+pub fn parse_coordinate(s: &str) -> Option<Coord> {
+  let mut chars = s.trim().chars();
+  let row = chars.next()?.to_ascii_uppercase();
+  if !row.is_ascii_uppercase() {
+    return None;
+  }
+  let col = chars.as_str().parse::<usize>().ok()?.checked_sub(1)?;
+  Coord::new(usize::from(row as u8 - b'A'), col)
+}
+
+/// a partly solved tracking board, one cell and its status per line like "C7 hit" or "D2 miss"
+pub fn parse_tracking(text: &str) -> Result<Vec<(Coordinate, Status)>, String> {
+  text
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty())
+    .map(|line| {
+      let (cell, status) = line
+        .split_once(char::is_whitespace)
+        .ok_or(format!("expected a cell and a status, got {:?}", line))?;
+      let cell = parse_coordinate(cell).ok_or(format!("invalid cell {:?}", cell))?;
+      Ok((cell.into(), status.trim().parse()?))
+    })
+    .collect()
+}
+
+impl From<Coord> for Coordinate {
+  fn from(coord: Coord) -> Self {
+    (coord.row, coord.col)
+  }
+}
+
+impl TryFrom<Coordinate> for Coord {
+  type Error = Coordinate;
+
+  fn try_from(coordinate: Coordinate) -> Result<Self, Self::Error> {
+    Self::new(coordinate.0, coordinate.1).ok_or(coordinate)
+  }
+}
+
+/// a (row, col) pair
+impl Serialize for Coord {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    Coordinate::from(*self).serialize(serializer)
+  }
+}
+
+/// checked to be on the board like `Coord::new`
+impl<'de> Deserialize<'de> for Coord {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let (row, col) = Coordinate::deserialize(deserializer)?;
+    Coord::new(row, col)
+      .ok_or_else(|| de::Error::custom(format!("cell {}.{} is off the board", row, col)))
+  }
+}
+
+/// how a finished game ended
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Outcome {
+  /// by the player at this turn, 0 is you
+  Win(usize),
+  /// the match clock ran out with as many ships sunk on both sides
+  Draw,
+}
 
 arg_enum! {
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
     pub enum Rule {
       Default, // single shots
       Fury,    // not more than total number of ships alive
       Charge,  // not more than number of killed ships + 1
+      Barrage, // fixed number of shots per turn
     }
 }
 
 arg_enum! {
-    #[derive(PartialEq, Debug)]
+    #[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
     pub enum Difficulty {
         Easy, // computer generates random shots without previous ones
         Hard, // computer generates shots based on analysis of hit/miss  data
+        Adaptive, // starts easy and targets more the better the opponent shoots
+    }
+}
+
+arg_enum! {
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum Formation {
+        Spread,  // ships apart, one in each quarter of the board
+        Cluster, // ships packed together in the middle
+        Edges,   // every ship against the border
+    }
+}
+
+impl Formation {
+  /// where the initial fleet goes, each ship's top left corner and rotation
+  fn placements(&self) -> Vec<(ShipType, Anchor, u16)> {
+    match self {
+      Formation::Spread => vec![
+        (ShipType::X, (1, 1), 90),
+        (ShipType::V, (1, 6), 90),
+        (ShipType::H, (6, 1), 90),
+        (ShipType::I, (6, 6), 90),
+      ],
+      Formation::Cluster => vec![
+        (ShipType::X, (3, 3), 90),
+        (ShipType::V, (3, 6), 90),
+        (ShipType::H, (6, 3), 90),
+        (ShipType::I, (6, 6), 180),
+      ],
+      Formation::Edges => vec![
+        (ShipType::X, (0, 0), 90),
+        (ShipType::V, (0, 7), 90),
+        (ShipType::H, (7, 0), 90),
+        // the line runs down the middle column of its box
+        (ShipType::I, (7, 8), 90),
+      ],
+    }
+  }
+}
+
+impl Rule {
+  pub fn description(&self) -> &'static str {
+    match *self {
+      Rule::Default => "Only one shot per turn",
+      Rule::Fury => "Shots per turn up to the number of your ships alive",
+      Rule::Charge => "One shot per turn plus one for every opponent ship sunk",
+      Rule::Barrage => "A fixed number of shots per turn",
+    }
+  }
+}
+
+impl Difficulty {
+  pub fn strategy(&self) -> Box<dyn FiringStrategy> {
+    match *self {
+      Difficulty::Easy => Box::new(EasyStrategy),
+      Difficulty::Hard => Box::new(HardStrategy::default()),
+      Difficulty::Adaptive => Box::new(AdaptiveStrategy::default()),
+    }
+  }
+}
+
+/// picks the computer's shots from its view of the opponent board
+pub trait FiringStrategy {
+  fn choose_shots(
+    &self,
+    board: &Board,
+    number_of_shots: usize,
+    rng: &mut StdRng,
+  ) -> BTreeSet<Coordinate>;
+
+  /// the opponent's share of shots that hit so far, told before every volley
+  fn observe_accuracy(&mut self, _accuracy: f64) {}
+}
+
+/// random shots without previous ones
+pub struct EasyStrategy;
+
+impl FiringStrategy for EasyStrategy {
+  fn choose_shots(
+    &self,
+    board: &Board,
+    number_of_shots: usize,
+    rng: &mut StdRng,
+  ) -> BTreeSet<Coordinate> {
+    let unfired = board.unfired_coordinates();
+    // only returns fewer shots when the board runs out of cells to fire at
+    let number_of_shots = number_of_shots.min(unfired.len());
+    let mut shots = BTreeSet::new();
+    while shots.len() < number_of_shots {
+      let shot = (rng.gen_range(0..ROWS), rng.gen_range(0..COLS));
+      if unfired.contains(&shot) {
+        shots.insert(shot);
+      }
+    }
+    shots
+  }
+}
+
+/// shots near previous hits, random ones when there are none
+pub struct HardStrategy {
+  /// hunt like a person, only next to one hit at a time instead of around all of them
+  pub fair: bool,
+  /// how far from a hit the shots land, fair hunting ignores it and keeps to direct neighbours
+  pub jitter: usize,
+  /// hunt where the most ships could still fit, which favours the center of the board
+  pub center_bias: bool,
+}
+
+impl Default for HardStrategy {
+  fn default() -> Self {
+    Self {
+      fair: false,
+      jitter: JITTER,
+      center_bias: false,
+    }
+  }
+}
+
+impl HardStrategy {
+  /// unfired cells right next to the first hit that still has any
+  fn fair_target(hits: &[Coordinate], unfired: &[Coordinate]) -> Vec<Coordinate> {
+    hits
+      .iter()
+      .map(|hit| {
+        adjacent_coordinates(*hit)
+          .filter(|c| c.0.abs_diff(hit.0) + c.1.abs_diff(hit.1) == 1)
+          .filter(|c| unfired.contains(c))
+          .collect::<Vec<_>>()
+      })
+      .find(|neighbours| !neighbours.is_empty())
+      .unwrap_or_default()
+  }
+
+  /// a random hit moved by up to `jitter` cells each way, a move off the board stays in the hit's
+  /// row or column
+  fn jitter_shot(hits: &[Coordinate], jitter: usize, rng: &mut StdRng) -> Coordinate {
+    let hit = hits.choose(rng).map_or((0, 0), |h| *h);
+    let jitter = jitter as i32;
+    let x = hit.0 as i32 + rng.gen_range(-jitter..=jitter);
+    let y = hit.1 as i32 + rng.gen_range(-jitter..=jitter);
+    let x = if x >= ROWS as i32 || x < 0 {
+      hit.0
+    } else {
+      x as usize
+    };
+    let y = if y >= COLS as i32 || y < 0 {
+      hit.1
+    } else {
+      y as usize
+    };
+    (x, y)
+  }
+}
+
+impl FiringStrategy for HardStrategy {
+  fn choose_shots(
+    &self,
+    board: &Board,
+    number_of_shots: usize,
+    rng: &mut StdRng,
+  ) -> BTreeSet<Coordinate> {
+    let previous_hits = board
+      .fired_positions()
+      .into_iter()
+      .filter(|p| p.status == Status::Hit)
+      .map(|p| p.coordinate)
+      .collect::<Vec<_>>();
+
+    let mut unfired = board.unfired_coordinates();
+    // ships never touch, so nothing can hide next to a sunk one
+    if board.no_touching {
+      let excluded = board.sunk_surroundings();
+      unfired.retain(|c| !excluded.contains(c));
+      if unfired.is_empty() {
+        unfired = board.unfired_coordinates();
+      }
+    }
+    let density = if self.center_bias {
+      board.placement_density()
+    } else {
+      vec![]
+    };
+    let mut shots = BTreeSet::new();
+    while shots.len() < number_of_shots && !unfired.is_empty() {
+      // try slots near previous hits
+      let near_shot = if self.fair {
+        Self::fair_target(&previous_hits, &unfired)
+          .choose(rng)
+          .copied()
+      } else if previous_hits
+        .iter()
+        .any(|hit| near_coordinates(*hit, self.jitter).any(|c| unfired.contains(&c)))
+      {
+        // some jittered shot lands on a cell left to fire at, so this ends
+        loop {
+          let shot = Self::jitter_shot(&previous_hits, self.jitter, rng);
+          if unfired.contains(&shot) {
+            break Some(shot);
+          }
+        }
+      } else {
+        None
+      };
+      // cells near previous hits may all be taken, fall back to random shots then
+      let shot = match near_shot {
+        Some(shot) => Some(shot),
+        // every cell keeps a chance, even one no ship fits in anymore
+        None if self.center_bias => unfired
+          .choose_weighted(rng, |c| density[c.0][c.1] + 1)
+          .ok()
+          .copied(),
+        None => unfired.choose(rng).copied(),
+      };
+      let shot = match shot {
+        Some(shot) => shot,
+        None => break,
+      };
+      unfired.retain(|c| *c != shot);
+      shots.insert(shot);
+    }
+    shots
+  }
+}
+
+/// opponent accuracy at which the adaptive computer always targets like the hard one
+const FULL_TARGETING_ACCURACY: f64 = 0.5;
+
+/// easy or hard shots per volley, hard ones more often the better the opponent shoots
+#[derive(Default)]
+pub struct AdaptiveStrategy {
+  /// chance of a hard volley, 0 plays like easy and 1 like hard
+  targeting: f64,
+}
+
+impl FiringStrategy for AdaptiveStrategy {
+  fn choose_shots(
+    &self,
+    board: &Board,
+    number_of_shots: usize,
+    rng: &mut StdRng,
+  ) -> BTreeSet<Coordinate> {
+    if rng.gen_bool(self.targeting) {
+      HardStrategy::default().choose_shots(board, number_of_shots, rng)
+    } else {
+      EasyStrategy.choose_shots(board, number_of_shots, rng)
     }
+  }
+
+  fn observe_accuracy(&mut self, accuracy: f64) {
+    self.targeting = (accuracy / FULL_TARGETING_ACCURACY).clamp(0.0, 1.0);
+  }
 }
 
 pub struct Game {
   pub rule: Rule,
+  /// number of sunk ships needed to win, all ships when unset
+  pub win_threshold: Option<usize>,
+  /// practice games allow undoing volleys
+  pub practice: bool,
+  /// shots per turn for the barrage rule
+  pub barrage_shots: usize,
+  /// sinking a ship reveals all its cells as killed on the tracking board, when off only the struck
+  /// cell is, for fog of war
+  pub reveal_full_ship_on_kill: bool,
+  /// how the computer picks its shots, defaults to the difficulty's strategy
+  pub strategy: Box<dyn FiringStrategy>,
+  /// break volley messages down per ship
+  pub verbose: bool,
+  /// the computer only remembers its latest shots this many cells deep, it never repeats one though
+  pub bot_memory: Option<usize>,
+  /// ships are placed with a free cell around them
+  no_touching: bool,
+  /// ship types each fleet is built from
+  fleet: Vec<ShipType>,
+  /// hits each ship cell takes before it's damaged
+  armor: usize,
+  /// the computer's fleet is the player's reflected left to right
+  mirrored: bool,
+  /// opponent ship cells shown as hit on your tracking board from the start
+  reveal_count: usize,
+  /// your fleet's formation, every new game starts with it
+  formation: Option<Formation>,
+  /// the computer's fleet from a board code or file, every new game starts with it
+  computer_board: Option<Board>,
+  /// hits and misses marked on your tracking board before the game, marked again on new games
+  preseeded: Vec<(Coordinate, Status)>,
+  /// players may move one undamaged ship once per game instead of firing
+  pub allow_maneuver: bool,
+  /// players may sink one of their own ships once per game for its size in extra shots next turn
+  pub allow_scuttle: bool,
   difficulty: Difficulty,
   players: [Player; 2],
-  winner: Option<usize>,
+  outcome: Option<Outcome>,
   turn: usize,
+  scores: [i32; 2],
+  undo_stack: Vec<VolleySnapshot>,
+  /// places the fleets
+  placement_rng: StdRng,
+  /// drives the bot's shots
+  rng: StdRng,
+  initial_players: [Player; 2],
+  history: Vec<Volley>,
+  maneuvers: Vec<Maneuver>,
+  scuttles: Vec<Scuttle>,
+  radars: Vec<Radar>,
+  sinkings: Vec<Sinking>,
 }
 
 impl Game {
   pub fn new(rule: Rule, difficulty: Difficulty) -> Self {
+    Self::with_seed(rule, difficulty, rand::random())
+  }
+
+  /// same seed gives the same fleets, ship ids included, and bot shots
+  pub fn with_seed(rule: Rule, difficulty: Difficulty, seed: u64) -> Self {
+    Self::with_seeds(rule, difficulty, seed, seed)
+  }
+
+  /// separate seeds for ship placement and bot shots, changing one leaves the other alone
+  pub fn with_seeds(
+    rule: Rule,
+    difficulty: Difficulty,
+    placement_seed: u64,
+    strategy_seed: u64,
+  ) -> Self {
+    let mut placement_rng = StdRng::seed_from_u64(placement_seed);
+    let players = [
+      Player::new(
+        false,
+        &ShipType::get_initial_ships(),
+        false,
+        &mut placement_rng,
+      ),
+      Player::new(
+        true,
+        &ShipType::get_initial_ships(),
+        false,
+        &mut placement_rng,
+      ),
+    ];
     Self {
       turn: 0,
-      winner: None,
-      players: [Player::new(), Player::default()],
+      outcome: None,
+      scores: [0; 2],
+      initial_players: players.clone(),
+      history: vec![],
+      maneuvers: vec![],
+      scuttles: vec![],
+      radars: vec![],
+      sinkings: vec![],
+      formation: None,
+      computer_board: None,
+      preseeded: vec![],
+      players,
       rule,
+      win_threshold: None,
+      practice: false,
+      barrage_shots: BARRAGE_SHOTS,
+      reveal_full_ship_on_kill: true,
+      strategy: difficulty.strategy(),
+      verbose: false,
+      bot_memory: None,
+      no_touching: false,
+      fleet: ShipType::get_initial_ships().to_vec(),
+      armor: 1,
+      mirrored: false,
+      reveal_count: 0,
+      allow_maneuver: false,
+      allow_scuttle: false,
       difficulty,
+      undo_stack: vec![],
+      placement_rng,
+      rng: StdRng::seed_from_u64(strategy_seed),
     }
   }
 
+  /// 0 is you, 1 the computer or the second player
+  pub fn player_by_turn(&self, turn: usize) -> &Player {
+    &self.players[turn]
+  }
+
   fn player_by_turn_mut(&mut self, turn: usize) -> &mut Player {
     &mut self.players[turn]
   }
 
-  fn generate_bot_firing_coordinates(&self) -> BTreeSet<Coordinate> {
-    let mut rng = rand::thread_rng();
+  /// shots for whoever's turn it is, the computer in a normal game
+  fn generate_bot_firing_coordinates(&mut self) -> BTreeSet<Coordinate> {
+    // the same allowance the user gets
+    let number_of_shots = self.max_shots();
 
-    let number_of_shots = match self.rule {
-      Rule::Default => 1,
-      Rule::Fury => self.computer().player_board().ships_alive().len(),
-      Rule::Charge => {
-        self.player().player_board().ships.len() - self.player().player_board().ships_alive().len()
-          + 1
+    // never ask for more shots than there are cells left to fire at
+    let board = self.players[self.turn].opponent_board();
+    let number_of_shots = number_of_shots.min(ROWS * COLS - board.fired_positions().len());
+
+    self.strategy.observe_accuracy(self.accuracy(1 - self.turn));
+    let memory = match self.bot_memory {
+      Some(memory) => memory,
+      None => {
+        return self
+          .strategy
+          .choose_shots(board, number_of_shots, &mut self.rng)
       }
     };
-
-    let mut shots = BTreeSet::new();
-
-    let previous_shots = self.computer().opponent_board().positions();
-
-    let previous_shots = previous_shots
-      .iter()
-      .filter(|p| p.status != Status::Live && p.status != Status::Space)
-      .collect::<Vec<_>>();
-
-    let previous_hits = previous_shots
+    let recent = self
+      .history
       .iter()
-      .filter(|p| p.status == Status::Hit)
+      .rev()
+      .filter(|volley| volley.turn == self.turn)
+      .flat_map(|volley| volley.shots.iter().map(|c| Coordinate::from(*c)))
+      .take(memory)
+      .collect::<BTreeSet<_>>();
+    let mut shots =
+      self
+        .strategy
+        .choose_shots(&board.forgetting(&recent), number_of_shots, &mut self.rng);
+    // forgotten cells may come up again, the full board still rules out repeats
+    let unfired = board.unfired_coordinates();
+    shots.retain(|c| unfired.contains(c));
+    let others = unfired
+      .into_iter()
+      .filter(|c| !shots.contains(c))
       .collect::<Vec<_>>();
-
-    while shots.len() < number_of_shots {
-      let shot = if self.difficulty == Difficulty::Easy {
-        get_random_coordinate(&mut rng, 0)
-      } else {
-        // Generate cords based on previous hits, skip missed/hit slots and try slots near previous hits
-        let shot = if previous_hits.is_empty() {
-          get_random_coordinate(&mut rng, 0)
-        } else {
-          let coord = previous_hits
-            .choose(&mut rng)
-            .map_or((0, 0), |r| r.coordinate);
-
-          let x_addition = POS_ADDITION.choose(&mut rng).unwrap_or(&0);
-          let y_addition = POS_ADDITION.choose(&mut rng).unwrap_or(&0);
-          let x = (coord.0 as i32) + x_addition;
-          let y = (coord.1 as i32) + y_addition;
-          let x = if x >= ROWS as i32 || x < 0 {
-            coord.0
-          } else {
-            x as usize
-          };
-          let y = if y >= COLS as i32 || y < 0 {
-            coord.1
-          } else {
-            y as usize
-          };
-          (x, y)
-        };
-
-        shot
-      };
-
-      if !previous_shots.iter().any(|p| p.coordinate == shot) {
-        shots.insert(shot);
-      }
-    }
-
+    let missing = number_of_shots.saturating_sub(shots.len());
+    shots.extend(others.choose_multiple(&mut self.rng, missing).copied());
     shots
   }
 
-  pub fn fire(&mut self, shots: &BTreeSet<Coordinate>, bot: bool) -> String {
+  pub fn fire(&mut self, shots: &BTreeSet<Coord>, bot: bool) -> String {
+    // coords are checked on construction, so indexing the board is safe
+    let coordinates = shots
+      .iter()
+      .map(|c| Coordinate::from(*c))
+      .collect::<BTreeSet<_>>();
     let player_index = self.turn;
     let opponent_index = 1 - player_index;
+    let win_threshold = self.win_threshold;
+    let reveal_full_ship_on_kill = self.reveal_full_ship_on_kill;
+    let verbose = self.verbose;
+    let practice = self.practice;
+    let turn = self.turn;
+    let outcome = self.outcome;
     let opponent = self.player_by_turn_mut(opponent_index);
     let opponent_board = opponent.player_board_mut();
-    let (response, lost) = opponent_board.take_fire(shots);
+    let target_positions = opponent_board.positions_at(coordinates.iter());
+    let target_status = opponent_board.firing_status.clone();
+    let alive_ships = opponent_board
+      .ships_alive()
+      .iter()
+      .map(|s| s.id.clone())
+      .collect::<Vec<_>>();
+    let wasted = coordinates
+      .iter()
+      .filter(|c| !opponent_board.is_near_ship(**c))
+      .count() as i32;
+    let (response, lost) = opponent_board.take_fire(&coordinates, reveal_full_ship_on_kill);
+    let lost = lost || win_threshold.is_some_and(|t| opponent_board.ships_sunk().len() >= t);
+    // the types are taken now, a maneuver or undo can't change what was sunk
+    let sunk_types = opponent_board
+      .ships_sunk()
+      .iter()
+      .filter(|s| alive_ships.contains(&s.id))
+      .map(|s| s.ship_type.clone())
+      .collect::<Vec<_>>();
+    let sunk_cells = sunk_types.iter().map(|t| t.cell_count()).sum::<usize>() as i32;
+    let score = self.scores[player_index];
+    self.scores[player_index] += sunk_cells * SHIP_CELL_POINTS - wasted * WASTED_SHOT_PENALTY;
 
     let player = self.player_by_turn_mut(player_index);
-    let message = player.opponent_board_mut().update_status(response, bot);
+    player.shots_fired += shots.len();
+    let name = player.name.clone();
+    if practice {
+      let tracking_positions = player.opponent_board().positions_at(response.keys());
+      let tracking_status = player.opponent_board().firing_status.clone();
+      self.undo_stack.push(VolleySnapshot {
+        turn,
+        outcome,
+        score,
+        target_positions,
+        tracking_positions,
+        target_status,
+        tracking_status,
+        alive_ships,
+      });
+    }
+    let volley = self.history.len();
+    self
+      .sinkings
+      .extend(sunk_types.into_iter().map(|ship_type| Sinking {
+        volley,
+        turn,
+        ship_type,
+      }));
+    self.history.push(Volley {
+      turn,
+      shots: shots.clone(),
+    });
+    let player = self.player_by_turn_mut(player_index);
+    let message =
+      player
+        .opponent_board_mut()
+        .update_status(response, bot, name.as_deref(), verbose);
     self.turn = opponent_index;
     if lost {
-      self.winner = Some(player_index);
+      self.outcome = Some(Outcome::Win(player_index));
+      // told from the side of the person at the keyboard
       if bot {
-        "You lost 🙁".into()
+        format!("{} lost 🙁", self.players[opponent_index].name())
       } else {
-        "You won 🙌".into()
+        format!("{} won 🙌", self.players[player_index].name())
       }
     } else {
       message
     }
   }
 
-  pub fn bot_fire(&mut self) -> String {
-    let shots = self.generate_bot_firing_coordinates();
-    self.fire(&shots, true)
-  }
+  /// revert the most recent volley, only available in practice games
+  pub fn undo(&mut self) -> bool {
+    if !self.practice {
+      return false;
+    }
+    match self.undo_stack.pop() {
+      Some(snapshot) => {
+        let VolleySnapshot {
+          turn,
+          outcome,
+          score,
+          target_positions,
+          tracking_positions,
+          target_status,
+          tracking_status,
+          alive_ships,
+        } = snapshot;
+        let player_index = turn;
+        let opponent_index = 1 - player_index;
 
-  pub fn is_user_turn(&self) -> bool {
-    self.turn == 0
-  }
+        let target_board = self.player_by_turn_mut(opponent_index).player_board_mut();
+        target_board.restore_positions(target_positions);
+        target_board.firing_status = target_status;
+        target_board
+          .ships
+          .iter_mut()
+          .for_each(|s| s.alive = alive_ships.contains(&s.id));
 
-  pub fn is_won(&self) -> bool {
-    self.winner.is_some()
-  }
+        let tracking_board = self.player_by_turn_mut(player_index).opponent_board_mut();
+        tracking_board.restore_positions(tracking_positions);
+        tracking_board.firing_status = tracking_status;
 
-  pub fn is_valid_rule(&self, existing_shots: usize) -> bool {
-    match self.rule {
-      Rule::Default => existing_shots < 1,
-      Rule::Fury => existing_shots < self.player().player_board().ships_alive().len(),
-      Rule::Charge => {
-        existing_shots
-          <= (self.computer().player_board().ships.len()
-            - self.computer().player_board().ships_alive().len())
+        if let Some(volley) = self.history.pop() {
+          let player = self.player_by_turn_mut(turn);
+          player.shots_fired = player.shots_fired.saturating_sub(volley.shots.len());
+        }
+        let volleys = self.history.len();
+        self.sinkings.retain(|s| s.volley < volleys);
+        self.turn = turn;
+        self.outcome = outcome;
+        self.scores[turn] = score;
+        true
       }
+      None => false,
     }
   }
 
-  pub fn player(&self) -> &Player {
-    &self.players[0]
+  pub fn history(&self) -> &[Volley] {
+    &self.history
   }
 
-  pub fn computer(&self) -> &Player {
-    &self.players[1]
+  /// ships sunk so far, in the order they went down
+  pub fn sinkings(&self) -> &[Sinking] {
+    &self.sinkings
   }
-}
 
-#[derive(PartialEq, Debug, Clone, Copy)]
-pub enum Status {
-  Live,
-  Miss,
-  Hit,
-  Kill,
-  Space,
-}
+  /// rebuild the game as it was after the first `move_index` volleys
+  pub fn state_at(&self, move_index: usize) -> Game {
+    let mut players = self.initial_players.clone();
+    players
+      .iter_mut()
+      .zip(self.players.iter())
+      .for_each(|(p, current)| p.name = current.name.clone());
 
-impl Display for Status {
-  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    let s = match *self {
-      Status::Live => "🚀",
-      Status::Miss => "❌",
-      Status::Hit => "💥",
-      Status::Kill => "💀",
-      Status::Space => " ",
+    let mut game = Game {
+      rule: self.rule,
+      win_threshold: self.win_threshold,
+      practice: false,
+      barrage_shots: self.barrage_shots,
+      reveal_full_ship_on_kill: self.reveal_full_ship_on_kill,
+      strategy: self.difficulty.strategy(),
+      verbose: self.verbose,
+      bot_memory: self.bot_memory,
+      no_touching: self.no_touching,
+      fleet: self.fleet.clone(),
+      armor: self.armor,
+      mirrored: self.mirrored,
+      reveal_count: self.reveal_count,
+      formation: self.formation,
+      computer_board: self.computer_board.clone(),
+      preseeded: self.preseeded.clone(),
+      allow_maneuver: self.allow_maneuver,
+      allow_scuttle: self.allow_scuttle,
+      difficulty: self.difficulty,
+      players,
+      outcome: None,
+      turn: 0,
+      scores: [0; 2],
+      undo_stack: vec![],
+      placement_rng: self.placement_rng.clone(),
+      rng: self.rng.clone(),
+      initial_players: self.initial_players.clone(),
+      history: vec![],
+      maneuvers: vec![],
+      scuttles: vec![],
+      radars: vec![],
+      sinkings: vec![],
     };
-    write!(f, "{}", s)
+    // the recorded maneuvers were valid when made, so they replay the same way
+    let _ = game.replay(
+      &self.history,
+      &self.maneuvers,
+      &self.scuttles,
+      &self.radars,
+      move_index,
+    );
+    game
   }
-}
 
-#[derive(PartialEq, Clone)]
-pub struct Player {
-  is_bot: bool,
-  boards: [Board; 2],
-}
-
-impl Player {
-  fn new() -> Self {
-    Self {
-      is_bot: false,
-      boards: [Board::new(true), Board::new(false)],
+  /// fire the first `count` volleys, with the maneuvers, scuttles and radar sweeps made in between
+  fn replay(
+    &mut self,
+    volleys: &[Volley],
+    maneuvers: &[Maneuver],
+    scuttles: &[Scuttle],
+    radars: &[Radar],
+    count: usize,
+  ) -> Result<(), String> {
+    for index in 0..=count.min(volleys.len()) {
+      for maneuver in maneuvers.iter().filter(|m| m.after == index) {
+        self.turn = maneuver.turn;
+        self.maneuver(maneuver.from, maneuver.to, Some(maneuver.rotation))?;
+      }
+      for scuttle in scuttles.iter().filter(|s| s.after == index) {
+        self.turn = scuttle.turn;
+        self.scuttle(scuttle.at)?;
+      }
+      for radar in radars.iter().filter(|r| r.after == index) {
+        self.turn = radar.turn;
+        self.radar(radar.center)?;
+      }
+      if let Some(volley) = volleys.get(index).filter(|_| index < count) {
+        self.turn = volley.turn;
+        self.fire(&volley.shots, volley.turn == 1);
+      }
     }
+    Ok(())
   }
 
-  pub fn player_board_mut(&mut self) -> &mut Board {
-    &mut self.boards[0]
+  pub fn bot_fire(&mut self) -> String {
+    // custom strategies may return shots off the board, those are dropped
+    let shots = self
+      .generate_bot_firing_coordinates()
+      .into_iter()
+      .filter_map(|c| Coord::try_from(c).ok())
+      .collect::<BTreeSet<_>>();
+    let bot = !self.is_user_turn();
+    self.fire(&shots, bot)
   }
-  pub fn player_board(&self) -> &Board {
-    &self.boards[0]
+
+  /// share of the player's shots so far that landed on a ship, 0 before the first one
+  pub fn accuracy(&self, turn: usize) -> f64 {
+    let ship_cells = self.players[1 - turn].player_board().ship_cells();
+    let shots = self
+      .history
+      .iter()
+      .filter(|v| v.turn == turn)
+      .flat_map(|v| v.shots.iter())
+      .map(|c| Coordinate::from(*c))
+      .collect::<Vec<_>>();
+    if shots.is_empty() {
+      return 0.0;
+    }
+    let hits = shots.iter().filter(|c| ship_cells.contains(c)).count();
+    hits as f64 / shots.len() as f64
   }
-  pub fn opponent_board_mut(&mut self) -> &mut Board {
-    &mut self.boards[1]
+
+  pub fn is_user_turn(&self) -> bool {
+    self.turn == 0
   }
-  pub fn opponent_board(&self) -> &Board {
-    &self.boards[1]
+
+  /// the game is over, won or drawn
+  pub fn is_won(&self) -> bool {
+    self.outcome.is_some()
   }
-}
 
-impl Default for Player {
-  fn default() -> Self {
-    Self {
-      is_bot: true,
-      ..Self::new()
-    }
+  pub fn difficulty(&self) -> Difficulty {
+    self.difficulty
   }
-}
 
-#[derive(PartialEq, Clone)]
-pub struct Board {
-  pub positions: Vec<Vec<Position>>,
-  ships: Vec<Ship>,
-  firing_status: BTreeMap<String, String>,
-}
+  pub fn outcome(&self) -> Option<Outcome> {
+    self.outcome
+  }
 
-impl Board {
-  fn new(is_self: bool) -> Self {
-    let mut rng = rand::thread_rng();
-    // create empty positions
-    let mut positions = (0..ROWS)
-      .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
-      .collect::<Vec<_>>();
+  pub fn winner(&self) -> Option<usize> {
+    match self.outcome? {
+      Outcome::Win(turn) => Some(turn),
+      Outcome::Draw => None,
+    }
+  }
 
-    let ships = if is_self {
-      let ship_types = ShipType::get_initial_ships();
-      ship_types
-        .iter()
-        .map(|s_type| {
-          let mut ship_placed = false;
-          let mut ship = Ship::new(s_type.clone());
-          // place ships on the board without overlap
-          // doing this in a while loop is sub optimal as this is causing
-          // infinite loop if number of ships are more than 4 currently
-          while !ship_placed {
-            let start_cords = get_random_coordinate(&mut rng, SHIP_SIZE);
-            if !ship.is_overlapping(&positions, start_cords) {
-              // draw ship on to board
-              if ship.draw(&mut positions, start_cords) {
-                ship_placed = true
-              }
-            } else {
-              ship = Ship::new(s_type.clone());
-            }
-          }
-          ship
-        })
-        .collect::<Vec<_>>()
-    } else {
-      vec![]
+  /// end the game when the match clock runs out, whoever sank more ships wins
+  pub fn end_by_clock(&mut self) -> Outcome {
+    let [player_sunk, computer_sunk] = self.ships_sunk();
+    // `ships_sunk` counts each player's own losses
+    let outcome = match computer_sunk.cmp(&player_sunk) {
+      std::cmp::Ordering::Greater => Outcome::Win(0),
+      std::cmp::Ordering::Less => Outcome::Win(1),
+      std::cmp::Ordering::Equal => Outcome::Draw,
     };
+    self.outcome = Some(outcome);
+    outcome
+  }
 
-    Self {
-      ships,
-      firing_status: BTreeMap::new(),
-      positions,
-    }
+  /// points for sunk ships by size, less a point per shot far from any ship
+  pub fn score(&self, player_index: usize) -> i32 {
+    self.scores[player_index]
   }
 
-  fn as_grid(&self) -> Vec<String> {
-    self
-      .positions
+  /// how the player on `turn` fired compared to hitting the cells of the ships they sank without a
+  /// miss
+  pub fn shot_analysis(&self, turn: usize) -> ShotAnalysis {
+    let cells = self
+      .sinkings
       .iter()
-      .map(|row| {
-        row
-          .iter()
-          .map(|c| c.to_string())
-          .collect::<Vec<_>>()
-          .join("")
-      })
-      .collect::<Vec<_>>()
+      .filter(|s| s.turn == turn)
+      .map(|s| s.ship_type.cell_count())
+      .sum::<usize>();
+    ShotAnalysis {
+      actual_shots: self.players[turn].total_shots(),
+      // armored cells take that many hits each
+      min_shots: cells * self.players[1 - turn].player_board().armor,
+    }
   }
 
-  fn ships_alive(&self) -> Vec<&Ship> {
-    self.ships.iter().filter(|s| s.alive).collect::<Vec<_>>()
+  /// ships lost by the player and the computer
+  pub fn ships_sunk(&self) -> [usize; 2] {
+    [
+      self.player().player_board().ships_sunk().len(),
+      self.computer().player_board().ships_sunk().len(),
+    ]
   }
 
-  fn find_ship_mut(&mut self, id: String) -> Option<&mut Ship> {
-    self.ships.iter_mut().find(|s| s.id == id)
+  /// start over with new fleets, keeping the rule, names and settings
+  pub fn reset(&mut self) {
+    let mut players = [
+      Player::new(
+        false,
+        &self.fleet,
+        self.no_touching,
+        &mut self.placement_rng,
+      ),
+      Player::new(true, &self.fleet, self.no_touching, &mut self.placement_rng),
+    ];
+    if let Some(board) = self.formation.and_then(|f| Board::with_formation(f).ok()) {
+      players[0].boards[0] = board;
+    }
+    // a layout with a ship the reflection can't draw is placed again
+    while self.mirrored {
+      if let Some(board) = players[0].player_board().mirror(&mut self.placement_rng) {
+        players[1].boards[0] = board;
+        break;
+      }
+      players[0] = Player::new(
+        false,
+        &self.fleet,
+        self.no_touching,
+        &mut self.placement_rng,
+      );
+    }
+    if let Some(board) = &self.computer_board {
+      players[1].boards[0] = board.clone();
+    }
+    players
+      .iter_mut()
+      .zip(self.players.iter())
+      .for_each(|(p, current)| p.name = current.name.clone());
+    self.initial_players = players.clone();
+    self.players = players;
+    self.turn = 0;
+    self.outcome = None;
+    self.scores = [0; 2];
+    self.undo_stack = vec![];
+    self.history = vec![];
+    self.maneuvers = vec![];
+    self.scuttles = vec![];
+    self.radars = vec![];
+    self.sinkings = vec![];
+    self.arm_fleets();
+    if self.reveal_count > 0 {
+      self.reveal();
+    }
+    // a new random fleet may not match the known hits, then they're left out
+    let preseeded = std::mem::take(&mut self.preseeded);
+    let _ = self.preseed_tracking(&preseeded);
   }
 
-  fn find_ship(&self, id: String) -> Option<&Ship> {
-    self.ships.iter().find(|s| s.id == id)
+  /// whether the player on turn may still move a ship
+  pub fn can_maneuver(&self) -> bool {
+    self.allow_maneuver && !self.players[self.turn].maneuvered
   }
 
-  fn positions(&self) -> Vec<&Position> {
+  /// move the ship at `from` so that cell lands on `to` instead of firing, turned to `rotation` if
+  /// given, once per game, ends the turn
+  pub fn maneuver(
+    &mut self,
+    from: Coord,
+    to: Coord,
+    rotation: Option<u16>,
+  ) -> Result<String, String> {
+    let player_index = self.turn;
+    if !self.can_maneuver() {
+      return Err("No maneuver left".into());
+    }
+    let board = self.players[player_index].player_board_mut();
+    let rotation = rotation
+      .or_else(|| board.rotation_at(from))
+      .ok_or("No ship there to move")?;
+    board.move_ship(from, to, Some(rotation))?;
+    self.players[player_index].maneuvered = true;
+    self.maneuvers.push(Maneuver {
+      turn: player_index,
+      after: self.history.len(),
+      from,
+      to,
+      rotation,
+    });
+    // volleys before the move can't be undone anymore
+    self.undo_stack.clear();
+    self.turn = 1 - player_index;
+    Ok("Maneuver: ship moved".into())
+  }
+
+  /// whether the player on turn may still sink one of their own ships
+  pub fn can_scuttle(&self) -> bool {
+    self.allow_scuttle && !self.scuttles.iter().any(|s| s.turn == self.turn)
+  }
+
+  /// sink your own ship at `at` instead of firing, once per game, its size is added to your next volley
+  pub fn scuttle(&mut self, at: Coord) -> Result<String, String> {
+    let player_index = self.turn;
+    if !self.can_scuttle() {
+      return Err("No scuttle left".into());
+    }
+    let board = self.players[player_index].player_board_mut();
+    if board.ships_alive().len() < 2 {
+      return Err("Your last ship can't be scuttled".into());
+    }
+    let (row, col) = at.into();
+    let ship_id = board.positions[row][col]
+      .ship_id
+      .clone()
+      .ok_or("No ship there to scuttle")?;
+    let ship_type = board.scuttle(ship_id)?;
+    self.scuttles.push(Scuttle {
+      turn: player_index,
+      after: self.history.len(),
+      at,
+    });
+    // volleys before the scuttle can't be undone anymore
+    self.undo_stack.clear();
+    self.turn = 1 - player_index;
+    Ok(format!(
+      "Scuttle: {} sunk, {} extra shots next turn",
+      ship_type.name(),
+      ship_type.cell_count()
+    ))
+  }
+
+  /// extra shots from a scuttle the player at `turn` hasn't fired since
+  fn scuttle_bonus(&self, turn: usize) -> usize {
+    let last_volley = self.history.iter().rposition(|v| v.turn == turn);
+    let board = self.players[turn].player_board();
     self
-      .positions
+      .scuttles
       .iter()
-      .flat_map(|pr| pr.iter())
-      .collect::<Vec<_>>()
+      .filter(|s| s.turn == turn && last_volley.is_none_or(|v| s.after > v))
+      .filter_map(|s| board.cell_info(s.at).ship)
+      .map(|ship| ship.ship_type.cell_count())
+      .sum()
   }
 
-  fn pos_by_ship(&self, id: String) -> Vec<&Position> {
-    self
-      .positions()
-      .into_iter()
-      .filter(|pc| pc.ship_id.is_some() && pc.ship_id.clone().unwrap() == id)
-      .collect::<Vec<_>>()
+  /// handicap showing `count` random opponent ship cells as hit, capped at the cells there are
+  pub fn set_reveal_count(&mut self, count: usize) {
+    self.reveal_count = count;
+    self.reveal();
   }
 
-  fn alive_pos_by_ship(&self, id: String) -> Vec<&Position> {
-    self
-      .pos_by_ship(id)
+  fn reveal(&mut self) {
+    let live = self.players[1]
+      .player_board()
+      .positions()
       .into_iter()
-      .filter(|pc| pc.status == Status::Live)
-      .collect::<Vec<_>>()
+      .filter(|p| p.status == Status::Live)
+      .map(|p| p.coordinate)
+      // known hits are shown anyway
+      .filter(|c| !self.preseeded.contains(&(*c, Status::Hit)))
+      .collect::<Vec<_>>();
+    let cells = live
+      .choose_multiple(&mut self.placement_rng, self.reveal_count)
+      .copied()
+      .collect::<Vec<_>>();
+    self.reveal_cells(&cells);
   }
 
-  fn take_fire(&mut self, shots: &BTreeSet<Coordinate>) -> (FiringResponse, bool) {
-    let mut response = BTreeMap::new();
-    for shot in shots {
-      let pos = self.positions[shot.0][shot.1].clone();
-      let mut status = Status::Miss;
-      if pos.status == Status::Live {
-        status = Status::Hit;
-        if let Some(id) = &pos.ship_id {
-          if self.alive_pos_by_ship(id.clone()).len() <= 1 {
-            let ship = self.find_ship_mut(id.clone());
-            if let Some(ship) = ship {
-              status = Status::Kill;
-              ship.alive = false;
-              let pos = self.pos_by_ship(id.clone());
-              pos.iter().for_each(|p| {
-                response.insert(p.coordinate, status);
-              });
-            }
-          }
-        }
-      }
-      if pos.status != Status::Hit && pos.status != Status::Kill {
-        self.positions[shot.0][shot.1].status = status;
+  /// mark `cells` as hit on your fresh tracking board, they still have to be fired at to sink a ship
+  fn reveal_cells(&mut self, cells: &[Coordinate]) {
+    for player in [&mut self.players[0], &mut self.initial_players[0]] {
+      let tracking = player.opponent_board_mut();
+      tracking
+        .positions
+        .iter_mut()
+        .flatten()
+        .filter(|p| p.status == Status::Hit)
+        .for_each(|p| p.status = Status::Space);
+      for (row, col) in cells {
+        tracking.positions[*row][*col].status = Status::Hit;
       }
-      response.insert(*shot, status);
     }
-    (response, self.ships_alive().is_empty())
   }
 
-  fn update_status(&mut self, response: FiringResponse, bot: bool) -> String {
-    let mut kill_count = 0;
-    let mut hit_count = 0;
-    let mut miss_count = 0;
-    for (shot, status) in response {
-      let pos = &mut self.positions[shot.0][shot.1];
-      if pos.status == Status::Space || pos.status == Status::Live || status == Status::Kill {
-        pos.status = status;
-      }
-      match status {
-        Status::Miss => miss_count += 1,
-        Status::Hit => hit_count += 1,
-        Status::Kill => kill_count += 1,
-        _ => {}
+  /// mark known hits and misses on your fresh tracking board, they have to match the opponent's fleet
+  pub fn preseed_tracking(&mut self, entries: &[(Coordinate, Status)]) -> Result<(), String> {
+    let fleet = self.players[1].player_board();
+    for ((row, col), status) in entries {
+      let ship = fleet
+        .positions
+        .get(*row)
+        .and_then(|r| r.get(*col))
+        .is_some_and(|p| p.ship_id.is_some());
+      if (*status == Status::Hit) != ship {
+        return Err(format!("{:#} doesn't match the opponent's fleet", status));
       }
     }
-    let mut msg: Vec<String> = if bot {
-      vec!["Computer have ".into()]
-    } else {
-      vec!["You have ".into()]
-    };
-    if kill_count > 0 {
-      msg.push("sunk a ship.".to_string());
-    } else {
-      msg.push(format!("{} hit.", hit_count));
-    }
-    if miss_count > 0 {
-      msg.push(format!(
-        " {} missed {}.",
-        if bot { "Computer" } else { "You" },
-        miss_count
-      ));
+    for player in [&mut self.players[0], &mut self.initial_players[0]] {
+      player.opponent_board_mut().apply_tracking(entries)?;
     }
-    msg.join("")
+    self.preseeded.extend_from_slice(entries);
+    Ok(())
   }
 
-  pub fn find_position_and_ship(&self, coordinate: Coordinate) -> (&Position, Option<&Ship>) {
-    let pos = &self.positions[coordinate.0][coordinate.1];
-    if pos.ship_id.is_some() {
-      (pos, self.find_ship(pos.ship_id.clone().unwrap()))
-    } else {
-      (pos, None)
-    }
+  /// cells marked `status` on the tracking board before the game
+  fn preseeded_cells(&self, status: Status) -> Vec<Coord> {
+    self
+      .preseeded
+      .iter()
+      .filter(|(_, s)| *s == status)
+      .filter_map(|(c, _)| Coord::try_from(*c).ok())
+      .collect()
   }
-}
 
-impl Display for Board {
-  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    let s = self.as_grid().join("\n");
-    write!(f, "{}", s)
+  /// cells revealed by the handicap at the start of the game, known hits aren't
+  fn revealed_cells(&self) -> Vec<Coordinate> {
+    self.initial_players[0]
+      .opponent_board()
+      .positions()
+      .into_iter()
+      .filter(|p| p.status == Status::Hit)
+      .map(|p| p.coordinate)
+      .filter(|c| !self.preseeded.contains(&(*c, Status::Hit)))
+      .collect()
   }
-}
-
-#[derive(PartialEq, Clone)]
-pub struct Position {
-  status: Status,
-  coordinate: Coordinate,
-  ship_id: Option<String>,
-}
 
-impl Position {
-  fn new(coordinate: Coordinate) -> Self {
-    Self {
-      coordinate,
-      status: Status::Space,
-      ship_id: None,
+  /// the shot the hard computer would take next from the player on turn, doesn't end the turn
+  pub fn hint(&mut self) -> Result<Coord, String> {
+    let player = &mut self.players[self.turn];
+    if player.hints == 0 {
+      return Err("No hints left".into());
+    }
+    let board = player.opponent_board();
+    if board.fired_positions().len() == ROWS * COLS {
+      return Err("Nothing left to fire at".into());
     }
+    // a copy of the bot's rng, asking for hints doesn't change its shots
+    let shot = HardStrategy::default()
+      .choose_shots(board, 1, &mut self.rng.clone())
+      .into_iter()
+      .next()
+      .and_then(|c| Coord::try_from(c).ok())
+      .ok_or("Nothing left to fire at")?;
+    player.hints -= 1;
+    Ok(shot)
   }
 
-  pub fn get_status(&self, ship: Option<&Ship>) -> Status {
-    if ship.is_some() && !ship.unwrap().alive {
-      Status::Kill
-    } else {
-      self.status
+  /// sweep the 3x3 region around `center` for ships instead of firing, ends the turn
+  pub fn radar(&mut self, center: Coord) -> Result<String, String> {
+    let player_index = self.turn;
+    let opponent_index = 1 - player_index;
+    if self.players[player_index].radar_sweeps == 0 {
+      return Err("No radar sweeps left".into());
     }
+    self.players[player_index].radar_sweeps -= 1;
+    let contact = self.players[opponent_index]
+      .player_board()
+      .region_has_ship(center);
+    self.radars.push(Radar {
+      turn: player_index,
+      after: self.history.len(),
+      center,
+    });
+    // volleys before the sweep can't be undone anymore
+    self.undo_stack.clear();
+    self.turn = opponent_index;
+
+    let (row, col) = center.into();
+    let corner = |row, col| Coord::new(row, col).map_or(String::default(), |c| c.to_string());
+    Ok(format!(
+      "Radar: {} in region {}–{}",
+      if contact { "contact" } else { "no contact" },
+      corner(row.saturating_sub(1), col.saturating_sub(1)),
+      corner((row + 1).min(ROWS - 1), (col + 1).min(COLS - 1))
+    ))
   }
-}
 
-impl Display for Position {
-  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    write!(f, "{}", self.status)
+  /// keep a free cell around every ship, places new fleets
+  pub fn set_no_touching(&mut self, no_touching: bool) {
+    self.no_touching = no_touching;
+    self.reset();
   }
-}
 
-#[derive(PartialEq, Clone)]
-pub struct Ship {
-  id: String,
-  rotation: u16,
-  alive: bool,
-  ship_type: ShipType,
-}
+  /// every ship cell takes this many hits before it's damaged, for new games
+  pub fn set_armor(&mut self, armor: usize) {
+    self.armor = armor.max(1);
+    self.arm_fleets();
+  }
 
-impl Ship {
-  fn new(ship_type: ShipType) -> Self {
-    Self {
-      id: Uuid::new_v4().to_string(),
-      rotation: ROTATIONS.choose(&mut rand::thread_rng()).map_or(0, |r| *r),
-      alive: true,
-      ship_type,
-    }
+  fn arm_fleets(&mut self) {
+    let armor = self.armor;
+    self
+      .players
+      .iter_mut()
+      .chain(self.initial_players.iter_mut())
+      .for_each(|p| p.player_board_mut().armor = armor);
   }
 
-  fn shape(&self) -> ShipShape {
-    self.ship_type.get_shape(self.rotation)
+  /// give the computer the player's fleet reflected, places new fleets
+  pub fn set_mirrored(&mut self, mirrored: bool) {
+    self.mirrored = mirrored;
+    self.reset();
   }
 
-  fn is_overlapping(&self, positions: &[Vec<Position>], start_cord: Coordinate) -> bool {
-    let mut ship_found = false;
-    if !positions.is_empty() && !positions[0].is_empty() {
-      let mut x = start_cord.0;
-      for row in &self.shape() {
-        let mut y = start_cord.1;
-        for _ in row {
-          if positions[x][y].status == Status::Live {
-            ship_found = true;
-          }
-          y += 1;
-        }
-        x += 1;
-      }
+  /// build both fleets from these ship types, places new fleets
+  pub fn set_fleet(&mut self, fleet: Vec<ShipType>) -> Result<(), GameError> {
+    if fleet.is_empty() {
+      // nothing to sink would win on the first shot
+      return Err(GameError::Placement(
+        "a fleet needs at least one ship".into(),
+      ));
     }
-    ship_found
+    self.fleet = fleet;
+    self.reset();
+    Ok(())
   }
 
-  fn draw(&self, positions: &mut [Vec<Position>], start_cord: Coordinate) -> bool {
-    let mut ship_drawn = false;
-    if !positions.is_empty() && !positions[0].is_empty() {
-      let shape = self.shape();
-
-      let mut x = start_cord.0;
-      for row in &shape {
-        let mut y = start_cord.1;
-        for col in row {
-          if Status::Live == *col {
-            positions[x][y].status = Status::Live;
-            positions[x][y].ship_id = Some(self.id.to_owned());
-            ship_drawn = true
-          }
-          y += 1;
-        }
-        x += 1;
+  /// between one ship and the whole fleet, 0 would win on the first volley
+  pub fn set_win_threshold(&mut self, threshold: Option<usize>) -> Result<(), GameError> {
+    match threshold {
+      Some(t) if t == 0 || t > self.fleet.len() => Err(GameError::Rule(format!(
+        "win threshold must be between 1 and {} ships, got {}",
+        self.fleet.len(),
+        t
+      ))),
+      _ => {
+        self.win_threshold = threshold;
+        Ok(())
       }
     }
-    ship_drawn
   }
-}
-
-#[derive(Clone, PartialEq)]
-enum ShipType {
-  X,
-  V,
-  H,
-  I,
-}
 
-impl ShipType {
-  fn get_shape(&self, rotation: u16) -> ShipShape {
-    let shape = match *self {
-      ShipType::X => [
-        [Status::Live, Status::Space, Status::Live],
-        [Status::Space, Status::Live, Status::Space],
-        [Status::Live, Status::Space, Status::Live],
-      ],
-      ShipType::V => [
-        [Status::Live, Status::Space, Status::Live],
-        [Status::Live, Status::Space, Status::Live],
-        [Status::Space, Status::Live, Status::Space],
-      ],
-      ShipType::H => [
-        [Status::Live, Status::Space, Status::Live],
-        [Status::Live, Status::Live, Status::Live],
-        [Status::Live, Status::Space, Status::Live],
-      ],
-      ShipType::I => [
-        [Status::Space, Status::Live, Status::Space],
-        [Status::Space, Status::Live, Status::Space],
-        [Status::Space, Status::Live, Status::Space],
-      ],
-    };
+  pub fn is_valid_rule(&self, existing_shots: usize) -> bool {
+    existing_shots < self.max_shots()
+  }
 
-    match rotation {
-      180 => reverse_cols_of_rows(transpose(shape)),
-      270 => reverse_rows_of_cols(reverse_cols_of_rows(shape)),
-      360 => reverse_rows_of_cols(transpose(shape)),
-      _ => shape,
+  /// shots the user may fire this turn under the active rule
+  pub fn max_shots(&self) -> usize {
+    let shooter = &self.players[self.turn];
+    let target = &self.players[1 - self.turn];
+    match self.rule {
+      Rule::Default => 1,
+      Rule::Fury => shooter.player_board().ships_alive().len(),
+      Rule::Charge => target.player_board().ships_sunk().len() + 1,
+      Rule::Barrage => self.barrage_shots,
     }
+    // a turn always has a shot, even with no barrage or no ship left
+    .max(1)
+    .saturating_add(self.scuttle_bonus(self.turn))
   }
 
-  fn get_initial_ships() -> [ShipType; 4] {
-    [Self::X, Self::V, Self::H, Self::I]
+  pub fn shots_left(&self, existing_shots: usize) -> usize {
+    self.max_shots().saturating_sub(existing_shots)
   }
-}
 
-fn get_random_coordinate(rng: &mut ThreadRng, threshold: usize) -> Coordinate {
-  (
-    rng.gen_range(0..(ROWS - threshold)),
-    rng.gen_range(0..(COLS - threshold)),
-  )
-}
-/**
- * transpose a 2D char array.
- */
-fn transpose(inp: ShipShape) -> ShipShape {
-  if inp.is_empty() {
-    //empty or unset array, nothing do to here
-    return inp;
+  pub fn player(&self) -> &Player {
+    &self.players[0]
   }
 
-  let mut out = inp;
+  pub fn computer(&self) -> &Player {
+    &self.players[1]
+  }
 
-  for (x, cols) in inp.iter().enumerate() {
-    for (y, _) in cols.iter().enumerate() {
-      out[y][x] = inp[x][y];
-    }
+  /// replace your fleet before the game starts, e.g. with a formation
+  pub fn set_player_board(&mut self, mut board: Board) {
+    board.armor = self.armor;
+    self.players[0].boards[0] = board.clone();
+    self.initial_players[0].boards[0] = board;
   }
-  out
-}
 
-/**
- * reverse columns of each rows in a 2d array.
- */
-fn reverse_cols_of_rows(inp: ShipShape) -> ShipShape {
-  if inp.is_empty() {
-    //empty or unset array, nothing do to here
-    return inp;
+  /// arrange your fleet in `formation`, play again keeps it
+  pub fn set_formation(&mut self, formation: Formation) -> Result<(), GameError> {
+    self.set_player_board(Board::with_formation(formation)?);
+    self.formation = Some(formation);
+    Ok(())
   }
-  let mut out = inp;
 
-  for (x, cols) in inp.iter().enumerate() {
-    for (y, _) in cols.iter().enumerate() {
-      out[x][cols.len() - y - 1] = inp[x][y];
+  /// replace the computer's fleet before the game starts, e.g. from a shared board code, play again
+  /// keeps it
+  pub fn set_computer_board(&mut self, board: Board) {
+    self.computer_board = Some(board.clone());
+    self.place_computer_board(board);
+  }
+
+  /// the computer's fleet for this game only
+  fn place_computer_board(&mut self, mut board: Board) {
+    board.armor = self.armor;
+    self.players[1].boards[0] = board.clone();
+    self.initial_players[1].boards[0] = board;
+    if self.reveal_count > 0 {
+      self.reveal();
     }
   }
-  out
-}
 
-/**
- * reverse rows of each column in a 2d array.
- */
-fn reverse_rows_of_cols(inp: ShipShape) -> ShipShape {
-  if inp.is_empty() {
-    //empty or unset array, nothing do to here
-    return inp;
+  /// version, rule, settings, both fleets and every volley as JSON
+  pub fn to_save(&self) -> String {
+    serde_json::to_string_pretty(&SaveData::new(self)).unwrap_or_default()
   }
 
-  let mut out = inp;
+  /// rebuild a game from `to_save`, replaying its volleys
+  pub fn from_save(save: &str) -> Result<Game, GameError> {
+    let invalid = |e: serde_json::Error| format!("invalid save file: {}", e);
+    serde_json::from_str::<SaveVersion>(save)
+      .map_err(invalid)
+      .and_then(SaveVersion::check)
+      .and_then(|_| serde_json::from_str::<SaveData>(save).map_err(invalid))
+      .and_then(SaveData::into_game)
+      .map_err(GameError::SaveLoad)
+  }
 
-  for (x, cols) in inp.iter().enumerate() {
-    for (y, _) in cols.iter().enumerate() {
-      out[inp.len() - x - 1][y] = inp[x][y];
-    }
+  /// the same as `to_save` in a compact binary form
+  pub fn to_save_bytes(&self) -> Vec<u8> {
+    binary_options()
+      .serialize(&SaveData::new(self))
+      .unwrap_or_default()
   }
-  out
-}
+
+  pub fn from_save_bytes(save: &[u8]) -> Result<Game, GameError> {
+    let invalid = |e: bincode::Error| format!("invalid save file: {}", e);
+    binary_options()
+      .allow_trailing_bytes()
+      .deserialize::<SaveVersion>(save)
+      .map_err(invalid)
+      .and_then(SaveVersion::check)
+      .and_then(|_| {
+        binary_options()
+          .deserialize::<SaveData>(save)
+          .map_err(invalid)
+      })
+      .and_then(SaveData::into_game)
+      .map_err(GameError::SaveLoad)
+  }
+
+  /// `.bin` files get the binary format, everything else JSON
+  pub fn save_to_path(&self, path: &Path) -> Result<(), GameError> {
+    let save = if is_binary_save(path) {
+      self.to_save_bytes()
+    } else {
+      self.to_save().into_bytes()
+    };
+    // write next to the save and swap it in, a crash never leaves half a save behind
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, save)
+      .and_then(|_| fs::rename(&tmp, path))
+      .map_err(|e| GameError::SaveLoad(format!("can't save to {}: {}", path.display(), e)))
+  }
+
+  pub fn load_from_path(path: &Path) -> Result<Game, GameError> {
+    let save = fs::read(path)
+      .map_err(|e| GameError::SaveLoad(format!("can't load {}: {}", path.display(), e)))?;
+    if is_binary_save(path) {
+      Self::from_save_bytes(&save)
+    } else {
+      let save = String::from_utf8(save)
+        .map_err(|_| GameError::SaveLoad(format!("{} isn't a JSON save file", path.display())))?;
+      Self::from_save(&save)
+    }
+  }
+
+  pub fn player_mut(&mut self) -> &mut Player {
+    &mut self.players[0]
+  }
+
+  pub fn computer_mut(&mut self) -> &mut Player {
+    &mut self.players[1]
+  }
+}
+
+fn is_binary_save(path: &Path) -> bool {
+  path.extension().is_some_and(|e| e == "bin")
+}
+
+/// everything a save file holds, written as JSON or as compact binary
+#[derive(Serialize, Deserialize)]
+struct SaveData {
+  /// always first, `SaveVersion` reads it on its own
+  version: u32,
+  rule: Rule,
+  difficulty: Difficulty,
+  barrage_shots: usize,
+  reveal_full_ship_on_kill: bool,
+  no_touching: bool,
+  win_threshold: Option<usize>,
+  /// fleet codes of you and the computer
+  boards: [String; 2],
+  /// computer ship cells revealed by the handicap
+  revealed: Vec<Coord>,
+  /// hits marked on the tracking board before the game
+  hits: Vec<Coord>,
+  /// misses marked on the tracking board before the game
+  missed: Vec<Coord>,
+  allow_maneuver: bool,
+  armor: usize,
+  allow_scuttle: bool,
+  volleys: Vec<Volley>,
+  maneuvers: Vec<Maneuver>,
+  scuttles: Vec<Scuttle>,
+  radars: Vec<Radar>,
+  /// radar sweeps you and the computer have left
+  radar_sweeps: [usize; 2],
+  /// hints you and the computer have left
+  hints: [usize; 2],
+}
+
+impl SaveData {
+  fn new(game: &Game) -> Self {
+    Self {
+      version: SAVE_VERSION,
+      rule: game.rule,
+      difficulty: game.difficulty,
+      barrage_shots: game.barrage_shots,
+      reveal_full_ship_on_kill: game.reveal_full_ship_on_kill,
+      no_touching: game.no_touching,
+      win_threshold: game.win_threshold,
+      boards: [
+        game.initial_players[0].player_board().to_code(),
+        game.initial_players[1].player_board().to_code(),
+      ],
+      revealed: game
+        .revealed_cells()
+        .into_iter()
+        .filter_map(|c| Coord::try_from(c).ok())
+        .collect(),
+      hits: game.preseeded_cells(Status::Hit),
+      missed: game.preseeded_cells(Status::Miss),
+      allow_maneuver: game.allow_maneuver,
+      armor: game.armor,
+      allow_scuttle: game.allow_scuttle,
+      volleys: game.history.clone(),
+      maneuvers: game.maneuvers.clone(),
+      scuttles: game.scuttles.clone(),
+      radars: game.radars.clone(),
+      radar_sweeps: [game.players[0].radar_sweeps, game.players[1].radar_sweeps],
+      hints: [game.players[0].hints, game.players[1].hints],
+    }
+  }
+
+  /// set up the saved boards and replay the volleys on them
+  fn into_game(self) -> Result<Game, String> {
+    self.check()?;
+    let mut game = Game::new(self.rule, self.difficulty);
+    game.barrage_shots = self.barrage_shots;
+    game.reveal_full_ship_on_kill = self.reveal_full_ship_on_kill;
+    game.set_no_touching(self.no_touching);
+    game.win_threshold = self.win_threshold;
+    let [player, computer] = &self.boards;
+    let player_board = Board::from_code(player).map_err(|e| e.to_string())?;
+    // play again builds fleets like the saved one
+    game.fleet = player_board
+      .ships
+      .iter()
+      .map(|ship| ship.ship_type.clone())
+      .collect();
+    let computer_board = Board::from_code(computer).map_err(|e| e.to_string())?;
+    // and mirrors them if the saved ones are
+    game.mirrored = player_board
+      .mirror(&mut game.placement_rng)
+      .is_some_and(|mirror| mirror.ship_cells() == computer_board.ship_cells());
+    game.set_player_board(player_board);
+    game.place_computer_board(computer_board);
+    game.reveal_count = self.revealed.len();
+    game.reveal_cells(
+      &self
+        .revealed
+        .iter()
+        .map(|c| (*c).into())
+        .collect::<Vec<_>>(),
+    );
+    game.preseed_tracking(
+      &self
+        .hits
+        .iter()
+        .map(|c| ((*c).into(), Status::Hit))
+        .chain(self.missed.iter().map(|c| ((*c).into(), Status::Miss)))
+        .collect::<Vec<_>>(),
+    )?;
+    game.allow_maneuver = self.allow_maneuver;
+    game.set_armor(self.armor);
+    game.allow_scuttle = self.allow_scuttle;
+    game.replay(
+      &self.volleys,
+      &self.maneuvers,
+      &self.scuttles,
+      &self.radars,
+      self.volleys.len(),
+    )?;
+    for ((player, sweeps), hints) in game
+      .players
+      .iter_mut()
+      .zip(self.radar_sweeps)
+      .zip(self.hints)
+    {
+      player.radar_sweeps = sweeps;
+      player.hints = hints;
+    }
+    Ok(game)
+  }
+
+  /// refuses data the replay would choke on
+  fn check(&self) -> Result<(), String> {
+    let mut turns = self
+      .volleys
+      .iter()
+      .map(|v| v.turn)
+      .chain(self.maneuvers.iter().map(|m| m.turn))
+      .chain(self.scuttles.iter().map(|s| s.turn))
+      .chain(self.radars.iter().map(|r| r.turn));
+    if turns.any(|turn| turn > 1) {
+      return Err("invalid turn in save file".into());
+    }
+    if self
+      .maneuvers
+      .iter()
+      .any(|m| !ROTATIONS.contains(&m.rotation))
+    {
+      return Err("invalid maneuver in save file".into());
+    }
+    if self.armor == 0 {
+      return Err("invalid armor in save file".into());
+    }
+    if self.radar_sweeps.iter().any(|s| *s > RADAR_SWEEPS) {
+      return Err("invalid radar sweeps in save file".into());
+    }
+    if self.hints.iter().any(|h| *h > HINTS) {
+      return Err("invalid hints in save file".into());
+    }
+    Ok(())
+  }
+}
+
+/// just the version of a save, read before the rest so other versions get a clear error
+#[derive(Deserialize)]
+struct SaveVersion {
+  version: u32,
+}
+
+impl SaveVersion {
+  fn check(self) -> Result<(), String> {
+    if self.version == SAVE_VERSION {
+      Ok(())
+    } else {
+      Err(unsupported_version(self.version))
+    }
+  }
+}
+
+/// bincode with variable length integers, most numbers in a save fit in a byte
+fn binary_options() -> impl bincode::Options {
+  bincode::DefaultOptions::new()
+}
+
+fn unsupported_version(version: u32) -> String {
+  format!(
+    "save file version {} isn't supported, expected {}",
+    version, SAVE_VERSION
+  )
+}
+
+/// shots fired in one turn, recorded for replays
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Volley {
+  pub turn: usize,
+  pub shots: BTreeSet<Coord>,
+}
+
+/// index of the first volley two histories don't share, none if they're the same
+pub fn diff_replays(a: &[Volley], b: &[Volley]) -> Option<usize> {
+  a.iter()
+    .zip(b)
+    .position(|(a, b)| a != b)
+    // one game went on after the other ended
+    .or_else(|| (a.len() != b.len()).then(|| a.len().min(b.len())))
+}
+
+/// shots a player fired against the fewest that sink the ships they sank
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct ShotAnalysis {
+  pub actual_shots: usize,
+  pub min_shots: usize,
+}
+
+impl ShotAnalysis {
+  /// share of the shots that were needed, 1 when every shot counted
+  pub fn efficiency(&self) -> f64 {
+    if self.actual_shots == 0 {
+      return 0.0;
+    }
+    self.min_shots as f64 / self.actual_shots as f64
+  }
+
+  pub fn grade(&self) -> char {
+    match self.efficiency() {
+      e if e >= 0.75 => 'A',
+      e if e >= 0.5 => 'B',
+      e if e >= 0.3 => 'C',
+      _ => 'D',
+    }
+  }
+}
+
+/// a ship sunk by a volley, recorded for the history
+#[derive(PartialEq, Debug, Clone)]
+pub struct Sinking {
+  /// index of the volley in the history
+  pub volley: usize,
+  /// player who fired it
+  pub turn: usize,
+  pub ship_type: ShipType,
+}
+
+/// a ship moved instead of firing, recorded to replay it
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Maneuver {
+  pub turn: usize,
+  /// volleys fired before it
+  pub after: usize,
+  /// a cell of the ship and where that cell moved to
+  pub from: Coord,
+  pub to: Coord,
+  /// the ship's rotation after the move
+  pub rotation: u16,
+}
+
+/// a ship sunk by its own player instead of firing, recorded to replay it
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Scuttle {
+  pub turn: usize,
+  /// volleys fired before it
+  pub after: usize,
+  /// a cell of the scuttled ship
+  pub at: Coord,
+}
+
+/// a region swept by radar instead of firing, recorded to replay it
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Radar {
+  pub turn: usize,
+  /// volleys fired before it
+  pub after: usize,
+  pub center: Coord,
+}
+
+/// state touched by a volley, kept to undo it
+struct VolleySnapshot {
+  turn: usize,
+  outcome: Option<Outcome>,
+  score: i32,
+  target_positions: Vec<Position>,
+  tracking_positions: Vec<Position>,
+  target_status: BTreeMap<String, String>,
+  tracking_status: BTreeMap<String, String>,
+  alive_ships: Vec<String>,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Status {
+  Live,
+  Miss,
+  Hit,
+  Kill,
+  Space,
+}
+
+impl Status {
+  pub const ALL: [Status; 5] = [
+    Status::Live,
+    Status::Miss,
+    Status::Hit,
+    Status::Kill,
+    Status::Space,
+  ];
+
+  /// number of terminal columns taken by the rendered status, emoji are double width
+  pub fn display_width(&self) -> usize {
+    match *self {
+      Status::Space => 1,
+      Status::Live | Status::Miss | Status::Hit | Status::Kill => 2,
+    }
+  }
+
+  /// columns taken by the label a cell shows, water stays blank in the accessible mode
+  pub fn label_width(&self, accessible: bool) -> usize {
+    if accessible && *self != Status::Space {
+      self.label(true).chars().count()
+    } else {
+      self.display_width()
+    }
+  }
+
+  /// the emoji, or a word screen readers can read out
+  pub fn label(&self, accessible: bool) -> String {
+    if accessible {
+      format!("{:#}", self)
+    } else {
+      self.to_string()
+    }
+  }
+}
+
+/// an emoji, or with `{:#}` a word
+impl Display for Status {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let s = match (*self, f.alternate()) {
+      (Status::Live, false) => "🚀",
+      (Status::Miss, false) => "❌",
+      (Status::Hit, false) => "💥",
+      (Status::Kill, false) => "💀",
+      (Status::Space, false) => " ",
+      (Status::Live, true) => "ship",
+      (Status::Miss, true) => "miss",
+      (Status::Hit, true) => "hit",
+      (Status::Kill, true) => "sunk",
+      (Status::Space, true) => "water",
+    };
+    write!(f, "{}", s)
+  }
+}
+
+/// the inverse of `Display`, takes the emoji or the word
+impl FromStr for Status {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    [
+      Status::Live,
+      Status::Miss,
+      Status::Hit,
+      Status::Kill,
+      Status::Space,
+    ]
+    .iter()
+    .copied()
+    .find(|status| status.to_string() == s || format!("{:#}", status) == s)
+    .ok_or_else(|| format!("unknown cell status {:?}", s))
+  }
+}
+
+#[derive(PartialEq, Clone)]
+pub struct Player {
+  is_bot: bool,
+  name: Option<String>,
+  boards: [Board; 2],
+  radar_sweeps: usize,
+  hints: usize,
+  /// moved a ship already
+  maneuvered: bool,
+  /// shots fired at the opponent over the whole game, repeats included
+  shots_fired: usize,
+}
+
+impl Player {
+  fn new<R: Rng>(is_bot: bool, ship_types: &[ShipType], no_touching: bool, rng: &mut R) -> Self {
+    let fleet =
+      Board::with_fleet(ship_types, no_touching, rng).expect("fleets always fit on the board");
+    let mut tracking = Board::new(false, rng);
+    tracking.no_touching = no_touching;
+    Self {
+      is_bot,
+      name: None,
+      boards: [fleet, tracking],
+      radar_sweeps: RADAR_SWEEPS,
+      hints: HINTS,
+      maneuvered: false,
+      shots_fired: 0,
+    }
+  }
+
+  pub fn radar_sweeps_left(&self) -> usize {
+    self.radar_sweeps
+  }
+
+  pub fn hints_left(&self) -> usize {
+    self.hints
+  }
+
+  pub fn total_shots(&self) -> usize {
+    self.shots_fired
+  }
+
+  pub fn set_name(&mut self, name: Option<String>) {
+    self.name = name;
+  }
+
+  /// configured name or the generic "You"/"Computer"
+  pub fn name(&self) -> &str {
+    match &self.name {
+      Some(name) => name,
+      None if self.is_bot => "Computer",
+      None => "You",
+    }
+  }
+
+  pub fn player_board_mut(&mut self) -> &mut Board {
+    &mut self.boards[0]
+  }
+  pub fn player_board(&self) -> &Board {
+    &self.boards[0]
+  }
+  pub fn opponent_board_mut(&mut self) -> &mut Board {
+    &mut self.boards[1]
+  }
+  pub fn opponent_board(&self) -> &Board {
+    &self.boards[1]
+  }
+}
+
+#[derive(PartialEq, Clone)]
+pub struct Board {
+  pub positions: Vec<Vec<Position>>,
+  ships: Vec<Ship>,
+  /// "damaged" or "sunk" by ship id, for every ship hit so far
+  firing_status: BTreeMap<String, String>,
+  /// ships on this board, or the one it tracks, never touch
+  no_touching: bool,
+  /// hits each ship cell takes before it's damaged, 1 without armor
+  armor: usize,
+}
+
+impl Board {
+  pub fn new<R: Rng>(is_self: bool, rng: &mut R) -> Self {
+    let ship_types = if is_self {
+      ShipType::get_initial_ships().to_vec()
+    } else {
+      vec![]
+    };
+    Self::with_fleet(&ship_types, false, rng).expect("initial fleet always fits on the board")
+  }
+
+  /// clear every cell and place a new initial fleet on your own board, reusing the grid
+  pub fn reset<R: Rng>(&mut self, is_self: bool, rng: &mut R) {
+    self
+      .positions
+      .iter_mut()
+      .flatten()
+      .for_each(|p| *p = Position::new(p.coordinate));
+    self.ships.clear();
+    self.firing_status.clear();
+    if is_self {
+      let ships = place_fleet(
+        &mut self.positions,
+        &ShipType::get_initial_ships(),
+        self.no_touching,
+        rng,
+      )
+      .expect("initial fleet always fits on the board");
+      self.ships.extend(ships);
+    }
+  }
+
+  /// place the given ships without overlap, fails only when no arrangement exists
+  fn with_fleet<R: Rng>(
+    ship_types: &[ShipType],
+    no_touching: bool,
+    rng: &mut R,
+  ) -> Result<Self, GameError> {
+    // create empty positions
+    let mut board = Self {
+      ships: vec![],
+      firing_status: BTreeMap::new(),
+      positions: (0..ROWS)
+        .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
+        .collect::<Vec<_>>(),
+      no_touching,
+      armor: 1,
+    };
+
+    // no need to search when the live cells alone don't fit, or a ship fits nowhere at all
+    let cells = ship_types.iter().map(|t| t.cell_count()).sum::<usize>();
+    let ships = if cells <= ROWS * COLS
+      && ship_types
+        .iter()
+        .all(|t| !board.legal_placements(t).is_empty())
+    {
+      place_fleet(&mut board.positions, ship_types, no_touching, rng)
+    } else {
+      None
+    }
+    .ok_or_else(|| {
+      GameError::Placement(format!("{} ships don't fit on the board", ship_types.len()))
+    })?;
+    board.ships = ships;
+    debug_assert_eq!(board.validate(), Ok(()));
+    Ok(board)
+  }
+
+  /// the fleet reflected left to right with new ship ids, none when a reflected ship isn't one of
+  /// its rotations
+  pub fn mirror<R: Rng>(&self, rng: &mut R) -> Option<Board> {
+    let mut positions = (0..ROWS)
+      .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
+      .collect::<Vec<_>>();
+    let ships = self
+      .ships
+      .iter()
+      .map(|ship| {
+        let mut cells = self
+          .pos_by_ship(ship.id.clone())
+          .iter()
+          .map(|p| (p.coordinate.0, COLS - 1 - p.coordinate.1))
+          .collect::<Vec<_>>();
+        cells.sort_unstable();
+        let mut mirrored = Ship {
+          id: ship_id(rng),
+          rotation: ship.rotation,
+          alive: true,
+          ship_type: ship.ship_type.clone(),
+        };
+        // every shape reflected is one of its rotations
+        let start = ROTATIONS.iter().find_map(|rotation| {
+          mirrored.rotation = *rotation;
+          let offsets = mirrored.offsets();
+          let start = (
+            cells.first()?.0 as isize - offsets.iter().map(|o| o.0).min()? as isize,
+            cells.iter().map(|c| c.1).min()? as isize - offsets.iter().map(|o| o.1).min()? as isize,
+          );
+          let mut drawn = mirrored.live_cells(start)?;
+          drawn.sort_unstable();
+          (drawn == cells).then_some(start)
+        })?;
+        mirrored.draw(&mut positions, start);
+        Some(mirrored)
+      })
+      .collect::<Option<Vec<_>>>()?;
+    Some(Self {
+      ships,
+      firing_status: BTreeMap::new(),
+      positions,
+      no_touching: self.no_touching,
+      armor: self.armor,
+    })
+  }
+
+  /// encode the fleet layout as `<type>.<rotation>.<row>.<col>` per ship, joined by `;`, a ship
+  /// is known by its place in the code so ids are left out to keep it short
+  pub fn to_code(&self) -> String {
+    self
+      .ships
+      .iter()
+      .filter_map(|ship| {
+        let (row, col) = ship.anchor(&self.pos_by_ship(ship.id.clone()))?;
+        Some(format!(
+          "{}.{}.{}.{}",
+          ship.ship_type.code(),
+          ship.rotation,
+          row,
+          col
+        ))
+      })
+      .collect::<Vec<_>>()
+      .join(";")
+  }
+
+  /// build a fresh board from a fleet layout created by `to_code`
+  pub fn from_code(code: &str) -> Result<Board, GameError> {
+    let ships = code
+      .split(';')
+      .enumerate()
+      .map(|(index, part)| {
+        let fields = part.split('.').collect::<Vec<_>>();
+        if fields.len() != 4 {
+          return Err(format!("invalid ship code {}", part));
+        }
+        let ship_type = ShipType::from_code(fields[0])?;
+        let rotation = fields[1]
+          .parse::<u16>()
+          .ok()
+          .filter(|r| ROTATIONS.contains(r))
+          .ok_or(format!("invalid rotation in {}", part))?;
+        // a shape box may stick out over the top or left edge as far as the ship stays on
+        let reach = 1 - SHIP_SIZE as isize;
+        let row = fields[2]
+          .parse::<isize>()
+          .ok()
+          .filter(|r| (reach..ROWS as isize).contains(r))
+          .ok_or(format!("invalid row in {}", part))?;
+        let col = fields[3]
+          .parse::<isize>()
+          .ok()
+          .filter(|c| (reach..COLS as isize).contains(c))
+          .ok_or(format!("invalid column in {}", part))?;
+        let ship = Ship {
+          id: index.to_string(),
+          rotation,
+          alive: true,
+          ship_type,
+        };
+        Ok((ship, (row, col)))
+      })
+      .collect::<Result<Vec<_>, String>>()
+      .map_err(GameError::Placement)?;
+    Self::with_ships(ships)
+  }
+
+  /// your fleet arranged in a formation instead of at random
+  pub fn with_formation(formation: Formation) -> Result<Board, GameError> {
+    let ships = formation
+      .placements()
+      .into_iter()
+      .enumerate()
+      .map(|(index, (ship_type, start, rotation))| {
+        let ship = Ship {
+          id: index.to_string(),
+          rotation,
+          alive: true,
+          ship_type,
+        };
+        (ship, start)
+      })
+      .collect();
+    Self::with_ships(ships)
+  }
+
+  /// draw ships at their top left corners, none may overlap or leave the board
+  fn with_ships(placed: Vec<(Ship, Anchor)>) -> Result<Board, GameError> {
+    let mut positions = (0..ROWS)
+      .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
+      .collect::<Vec<_>>();
+    if placed.is_empty() {
+      return Err(GameError::Placement(
+        "a fleet needs at least one ship".into(),
+      ));
+    }
+    let mut ships = vec![];
+    for (ship, start) in placed {
+      if ship.is_overlapping(&positions, start) {
+        return Err(GameError::Placement(format!(
+          "ship {} overlaps another ship",
+          ship.id
+        )));
+      }
+      if !ship.draw(&mut positions, start) {
+        return Err(GameError::Placement(format!(
+          "ship {} is off the board",
+          ship.id
+        )));
+      }
+      ships.push(ship);
+    }
+
+    let board = Self {
+      ships,
+      firing_status: BTreeMap::new(),
+      positions,
+      no_touching: false,
+      armor: 1,
+    };
+    board.validate().map_err(GameError::Placement)?;
+    Ok(board)
+  }
+
+  /// read a board code written to a file, surrounding whitespace is ignored
+  pub fn load_from_path(path: &Path) -> Result<Board, GameError> {
+    Self::from_code(fs::read_to_string(path)?.trim())
+  }
+
+  /// check board invariants, ship cells must belong to a known ship and match its shape
+  pub fn validate(&self) -> Result<(), String> {
+    for pos in self.positions() {
+      match (&pos.ship_id, pos.status) {
+        (Some(id), Status::Live | Status::Hit | Status::Kill) => {
+          if self.find_ship(id.clone()).is_none() {
+            return Err(format!(
+              "position {:?} refers to unknown ship {}",
+              pos.coordinate, id
+            ));
+          }
+        }
+        (Some(id), status) => {
+          return Err(format!(
+            "position {:?} of ship {} has invalid status {:?}",
+            pos.coordinate, id, status
+          ));
+        }
+        (None, _) => {}
+      }
+    }
+    // overlapping ships overwrite each other's cells, so counts won't match the shape
+    for ship in &self.ships {
+      let expected = ship.footprint();
+      let found = self.pos_by_ship(ship.id.clone()).len();
+      if found != expected {
+        return Err(format!(
+          "ship {} occupies {} cells, expected {}",
+          ship.id, found, expected
+        ));
+      }
+    }
+    Ok(())
+  }
+
+  fn as_grid(&self) -> Vec<String> {
+    self
+      .positions
+      .iter()
+      .map(|row| {
+        row
+          .iter()
+          .map(|c| c.to_string())
+          .collect::<Vec<_>>()
+          .join("")
+      })
+      .collect::<Vec<_>>()
+  }
+
+  fn ships_alive(&self) -> Vec<&Ship> {
+    self.ships.iter().filter(|s| s.alive).collect::<Vec<_>>()
+  }
+
+  fn ships_sunk(&self) -> Vec<&Ship> {
+    self.ships.iter().filter(|s| !s.alive).collect::<Vec<_>>()
+  }
+
+  fn find_ship_mut(&mut self, id: String) -> Option<&mut Ship> {
+    self.ships.iter_mut().find(|s| s.id == id)
+  }
+
+  fn find_ship(&self, id: String) -> Option<&Ship> {
+    self.ships.iter().find(|s| s.id == id)
+  }
+
+  /// positions already fired at on an opponent board
+  fn fired_positions(&self) -> Vec<&Position> {
+    self
+      .positions()
+      .into_iter()
+      .filter(|p| p.status != Status::Live && p.status != Status::Space)
+      .collect::<Vec<_>>()
+  }
+
+  /// a copy of a tracking board that only shows the shots at `remembered`
+  fn forgetting(&self, remembered: &BTreeSet<Coordinate>) -> Board {
+    let mut board = self.clone();
+    board
+      .positions
+      .iter_mut()
+      .flatten()
+      .filter(|p| !remembered.contains(&p.coordinate))
+      .for_each(|p| p.status = Status::Space);
+    board
+  }
+
+  /// coordinates not fired at yet on an opponent board
+  fn unfired_coordinates(&self) -> Vec<Coordinate> {
+    self
+      .positions()
+      .into_iter()
+      .filter(|p| p.status == Status::Live || p.status == Status::Space)
+      .map(|p| p.coordinate)
+      .collect::<Vec<_>>()
+  }
+
+  /// first cell after `coordinate` in reading order not fired at yet, wrapping around
+  pub fn next_unfired_from(&self, coordinate: Coordinate) -> Option<Coordinate> {
+    let unfired = self.unfired_coordinates();
+    unfired
+      .iter()
+      .find(|c| **c > coordinate)
+      .or_else(|| unfired.first())
+      .copied()
+  }
+
+  /// whether a ship cell not hit yet lies in the 3x3 region around `center`
+  pub fn region_has_ship(&self, center: Coord) -> bool {
+    adjacent_coordinates(center.into()).any(|(x, y)| {
+      let pos = &self.positions[x][y];
+      pos.ship_id.is_some() && pos.status == Status::Live
+    })
+  }
+
+  /// unfired cells next to a sunk ship on an opponent board
+  fn sunk_surroundings(&self) -> BTreeSet<Coordinate> {
+    self
+      .positions()
+      .into_iter()
+      .filter(|p| p.status == Status::Kill)
+      .flat_map(|p| adjacent_coordinates(p.coordinate))
+      .filter(|c| {
+        let status = self.positions[c.0][c.1].status;
+        status == Status::Live || status == Status::Space
+      })
+      .collect()
+  }
+
+  /// whether `coordinate` is on or next to a ship cell, hit or not
+  pub fn is_near_ship(&self, coordinate: Coordinate) -> bool {
+    self
+      .ship_cells()
+      .iter()
+      .any(|cell| cell.0.abs_diff(coordinate.0) <= 1 && cell.1.abs_diff(coordinate.1) <= 1)
+  }
+
+  /// cells covered by ships, whether hit or not
+  pub fn ship_cells(&self) -> Vec<Coordinate> {
+    self
+      .positions()
+      .into_iter()
+      .filter(|p| p.ship_id.is_some())
+      .map(|p| p.coordinate)
+      .collect::<Vec<_>>()
+  }
+
+  /// anchors and rotations the ship could be placed at on this board, rotations drawing the same
+  /// cells are listed once
+  pub fn legal_placements(&self, ship_type: &ShipType) -> Vec<(Anchor, u16)> {
+    let mut ship = Ship {
+      id: String::default(),
+      rotation: 0,
+      alive: true,
+      ship_type: ship_type.clone(),
+    };
+    placement_candidates(ship_type)
+      .into_iter()
+      .filter(|(start, rotation)| {
+        ship.rotation = *rotation;
+        is_legal_placement(&self.positions, &ship, *start, self.no_touching)
+      })
+      .collect()
+  }
+
+  /// placements of the initial fleet's ships covering each cell without crossing a miss or a
+  /// sunk ship, the more there are the likelier a ship is there
+  fn placement_density(&self) -> Vec<Vec<usize>> {
+    let mut density = vec![vec![0; COLS]; ROWS];
+    for ship_type in ShipType::get_initial_ships().iter() {
+      let mut ship = Ship {
+        id: String::default(),
+        rotation: 0,
+        alive: true,
+        ship_type: ship_type.clone(),
+      };
+      for (start, rotation) in self.legal_placements(ship_type) {
+        ship.rotation = rotation;
+        let cells = ship.live_cells(start).unwrap_or_default();
+        let open = cells
+          .iter()
+          .all(|(x, y)| !matches!(self.positions[*x][*y].status, Status::Miss | Status::Kill));
+        if open {
+          cells.iter().for_each(|(x, y)| density[*x][*y] += 1);
+        }
+      }
+    }
+    density
+  }
+
+  /// share of the board's cells taken by ships, hit or not
+  pub fn occupancy(&self) -> f64 {
+    self.ship_cells().len() as f64 / (ROWS * COLS) as f64
+  }
+
+  fn positions(&self) -> Vec<&Position> {
+    self
+      .positions
+      .iter()
+      .flat_map(|pr| pr.iter())
+      .collect::<Vec<_>>()
+  }
+
+  fn positions_at<'a>(&self, coordinates: impl Iterator<Item = &'a Coordinate>) -> Vec<Position> {
+    coordinates
+      .map(|c| self.positions[c.0][c.1].clone())
+      .collect::<Vec<_>>()
+  }
+
+  fn restore_positions(&mut self, positions: Vec<Position>) {
+    for pos in positions {
+      let (x, y) = pos.coordinate;
+      self.positions[x][y] = pos;
+    }
+  }
+
+  fn pos_by_ship(&self, id: String) -> Vec<&Position> {
+    self
+      .positions()
+      .into_iter()
+      .filter(|pc| pc.ship_id.is_some() && pc.ship_id.clone().unwrap() == id)
+      .collect::<Vec<_>>()
+  }
+
+  fn alive_pos_by_ship(&self, id: String) -> Vec<&Position> {
+    self
+      .pos_by_ship(id)
+      .into_iter()
+      .filter(|pc| pc.status == Status::Live)
+      .collect::<Vec<_>>()
+  }
+
+  /// ids of the ships hit so far and whether they're damaged or sunk
+  pub fn firing_status(&self) -> &BTreeMap<String, String> {
+    &self.firing_status
+  }
+
+  /// name of the ship `id`, numbered when the fleet has more of its type, e.g. "Line #2"
+  pub fn ship_label(&self, id: &str) -> Option<String> {
+    let ship = self.ships.iter().find(|s| s.id == id)?;
+    let same_type = self
+      .ships
+      .iter()
+      .filter(|s| s.ship_type == ship.ship_type)
+      .collect::<Vec<_>>();
+    let name = ship.ship_type.name();
+    Some(if same_type.len() > 1 {
+      let number = same_type.iter().position(|s| s.id == id).unwrap_or(0) + 1;
+      format!("{} #{}", name, number)
+    } else {
+      name.to_string()
+    })
+  }
+
+  fn record_ship_status(&mut self, id: &str, status: Status) {
+    let id = id.to_string();
+    match status {
+      Status::Kill => {
+        self.firing_status.insert(id, "sunk".to_string());
+      }
+      Status::Hit => {
+        self
+          .firing_status
+          .entry(id)
+          .or_insert_with(|| "damaged".to_string());
+      }
+      _ => {}
+    }
+  }
+
+  /// rotation of the ship at `at`, if there is one
+  pub fn rotation_at(&self, at: Coord) -> Option<u16> {
+    let (row, col) = at.into();
+    let id = self.positions[row][col].ship_id.clone()?;
+    self.find_ship(id).map(|ship| ship.rotation)
+  }
+
+  /// the undamaged ship at `from` turned to `rotation`, or kept as it is, and the start it moves
+  /// to so that cell lands on `to`, negative when it sticks out over the top or left edge
+  fn moved_ship(
+    &self,
+    from: Coord,
+    to: Coord,
+    rotation: Option<u16>,
+  ) -> Result<(Ship, Anchor), String> {
+    let (row, col) = from.into();
+    let mut ship = self.positions[row][col]
+      .ship_id
+      .clone()
+      .and_then(|id| self.find_ship(id))
+      .cloned()
+      .ok_or("No ship there to move")?;
+    let cells = self.pos_by_ship(ship.id.clone());
+    if cells.iter().any(|p| p.status != Status::Live || p.hits > 0) {
+      return Err("Only undamaged ships can move".into());
+    }
+    let anchor = ship.anchor(&cells).ok_or("No ship there to move")?;
+    // turning keeps the shape's box in place
+    ship.rotation = rotation.unwrap_or(ship.rotation);
+    let (to_row, to_col) = to.into();
+    let start = (
+      anchor.0 + to_row as isize - row as isize,
+      anchor.1 + to_col as isize - col as isize,
+    );
+    Ok((ship, start))
+  }
+
+  /// the grid without `ship` and where it's drawn instead, none when it doesn't fit at `start`
+  fn move_target(&self, ship: &Ship, start: Anchor) -> Option<(Vec<Vec<Position>>, Anchor)> {
+    let mut positions = self.positions.clone();
+    ship.erase(&mut positions);
+    let fits = ship.live_cells(start).is_some_and(|cells| {
+      cells
+        .iter()
+        .all(|(x, y)| positions[*x][*y].status == Status::Space)
+    }) && !(self.no_touching && ship.is_touching(&positions, start));
+    fits.then_some((positions, start))
+  }
+
+  /// shift the undamaged ship at `from` so that cell lands on `to`, turned to `rotation` if given,
+  /// only onto water nobody fired at
+  fn move_ship(&mut self, from: Coord, to: Coord, rotation: Option<u16>) -> Result<(), String> {
+    let (ship, start) = self.moved_ship(from, to, rotation)?;
+    match self.move_target(&ship, start) {
+      Some((mut positions, start)) => {
+        ship.draw(&mut positions, start);
+        self.positions = positions;
+        if let Some(moved) = self.find_ship_mut(ship.id.clone()) {
+          moved.rotation = ship.rotation;
+        }
+        Ok(())
+      }
+      None => Err(format!("The {} doesn't fit there", ship.ship_type.name())),
+    }
+  }
+
+  /// the cells on the board a move would cover and whether the ship fits there
+  pub fn move_preview(
+    &self,
+    from: Coord,
+    to: Coord,
+    rotation: Option<u16>,
+  ) -> Result<(Vec<Coordinate>, bool), String> {
+    let (ship, start) = self.moved_ship(from, to, rotation)?;
+    let cells = ship
+      .offsets()
+      .into_iter()
+      .filter_map(|(x, y)| {
+        let row = usize::try_from(start.0 + x as isize).ok()?;
+        let col = usize::try_from(start.1 + y as isize).ok()?;
+        Coord::new(row, col).map(Coordinate::from)
+      })
+      .collect();
+    Ok((cells, self.move_target(&ship, start).is_some()))
+  }
+
+  /// sink one of your own ships, all its cells become killed
+  fn scuttle(&mut self, ship_id: String) -> Result<ShipType, String> {
+    let ship = self
+      .find_ship_mut(ship_id.clone())
+      .ok_or("No ship there to scuttle")?;
+    if !ship.alive {
+      return Err(format!("The {} is already sunk", ship.ship_type.name()));
+    }
+    ship.alive = false;
+    let ship_type = ship.ship_type.clone();
+    self
+      .positions
+      .iter_mut()
+      .flatten()
+      .filter(|p| p.ship_id.as_ref() == Some(&ship_id))
+      .for_each(|p| p.status = Status::Kill);
+    Ok(ship_type)
+  }
+
+  fn take_fire(
+    &mut self,
+    shots: &BTreeSet<Coordinate>,
+    reveal_full_ship_on_kill: bool,
+  ) -> (FiringResponse, bool) {
+    let mut response = BTreeMap::new();
+    for shot in shots {
+      let pos = self.positions[shot.0][shot.1].clone();
+      let ship = pos
+        .ship_id
+        .as_ref()
+        .and_then(|id| self.find_ship(id.clone()))
+        .map(|s| (s.id.clone(), s.ship_type.clone()));
+      let mut status = Status::Miss;
+      if pos.status == Status::Live && pos.hits + 1 < self.armor {
+        // the armor takes the shot, the cell stays live but the shooter learns it's a ship
+        self.positions[shot.0][shot.1].hits += 1;
+        status = Status::Live;
+      } else if pos.status == Status::Live {
+        // mark the hit first, a ship of any size sinks once none of its cells are live
+        self.positions[shot.0][shot.1].status = Status::Hit;
+        status = Status::Hit;
+        if let Some(id) = &pos.ship_id {
+          if self.alive_pos_by_ship(id.clone()).is_empty() {
+            // a ship only sinks once, its wreck never reports another kill
+            if let Some(sunk) = self.find_ship_mut(id.clone()).filter(|s| s.alive) {
+              status = Status::Kill;
+              sunk.alive = false;
+              if reveal_full_ship_on_kill {
+                let pos = self.pos_by_ship(id.clone());
+                pos.iter().for_each(|p| {
+                  response.insert(
+                    p.coordinate,
+                    ShotResult {
+                      status,
+                      ship: ship.clone(),
+                    },
+                  );
+                });
+              }
+            }
+          }
+        }
+      }
+      if pos.status != Status::Hit && pos.status != Status::Kill {
+        self.positions[shot.0][shot.1].status = status;
+      }
+      if let Some((id, _)) = &ship {
+        self.record_ship_status(id, status);
+      }
+      // don't downgrade cells of a ship sunk earlier in the same volley
+      response.entry(*shot).or_insert(ShotResult { status, ship });
+    }
+    (response, self.ships_alive().is_empty())
+  }
+
+  fn update_status(
+    &mut self,
+    response: FiringResponse,
+    bot: bool,
+    name: Option<&str>,
+    verbose: bool,
+  ) -> String {
+    let subject = name.unwrap_or(if bot { "Computer" } else { "You" });
+    let breakdown = if verbose {
+      Some(ship_breakdown(&response))
+    } else {
+      None
+    };
+    let mut kill_count = 0;
+    let mut hit_count = 0;
+    let mut miss_count = 0;
+    let mut armor_count = 0;
+    for (shot, ShotResult { status, ship }) in response {
+      if let Some((id, _)) = &ship {
+        self.record_ship_status(id, status);
+      }
+      let pos = &mut self.positions[shot.0][shot.1];
+      if pos.status == Status::Space || pos.status == Status::Live || status == Status::Kill {
+        pos.status = status;
+      }
+      match status {
+        Status::Miss => miss_count += 1,
+        Status::Hit => hit_count += 1,
+        Status::Kill => kill_count += 1,
+        Status::Live => armor_count += 1,
+        _ => {}
+      }
+    }
+    if let Some(breakdown) = breakdown {
+      return format!("{}: {}.", subject, breakdown);
+    }
+    let mut msg: Vec<String> = vec![format!(
+      "{} {} ",
+      subject,
+      if name.is_some() { "has" } else { "have" }
+    )];
+    if kill_count > 0 {
+      msg.push("sunk a ship.".to_string());
+    } else {
+      msg.push(format!("{} hit.", hit_count));
+    }
+    if armor_count > 0 {
+      msg.push(format!(" {} dented armor {}.", subject, armor_count));
+    }
+    if miss_count > 0 {
+      msg.push(format!(" {} missed {}.", subject, miss_count));
+    }
+    msg.join("")
+  }
+
+  fn find_position_and_ship(&self, coordinate: Coord) -> (&Position, Option<&Ship>) {
+    let (row, col) = coordinate.into();
+    let pos = &self.positions[row][col];
+    if pos.ship_id.is_some() {
+      (pos, self.find_ship(pos.ship_id.clone().unwrap()))
+    } else {
+      (pos, None)
+    }
+  }
+
+  /// status of the cell and the ship on it, tracking boards never know about ships
+  pub fn cell_info(&self, coordinate: Coord) -> CellInfo {
+    let (pos, ship) = self.find_position_and_ship(coordinate);
+    CellInfo {
+      status: pos.get_status(ship),
+      ship: ship.map(|s| ShipHealth {
+        ship_type: s.ship_type.clone(),
+        cells_afloat: self.alive_pos_by_ship(s.id.clone()).len(),
+        alive: s.alive,
+      }),
+    }
+  }
+
+  /// what a tracking board showed in the cells around `at`, row by row
+  pub fn clues_around(&self, at: Coord) -> Vec<(Coord, Clue)> {
+    let at = Coordinate::from(at);
+    adjacent_coordinates(at)
+      .filter(|c| *c != at)
+      .filter_map(|c| {
+        let clue = match self.positions[c.0][c.1].status {
+          Status::Space => Clue::Open,
+          Status::Miss => Clue::Miss,
+          // a dented armored cell is a hit too, the ship is still there
+          Status::Hit | Status::Live => Clue::Hit,
+          Status::Kill => Clue::Wreck,
+        };
+        Some((Coord::try_from(c).ok()?, clue))
+      })
+      .collect()
+  }
+
+  /// mark cells of a fresh tracking board as already hit or missed, all or nothing
+  pub fn apply_tracking(&mut self, entries: &[(Coordinate, Status)]) -> Result<(), String> {
+    for ((row, col), status) in entries {
+      let current = self
+        .positions
+        .get(*row)
+        .and_then(|r| r.get(*col))
+        .ok_or(format!("cell {}.{} is off the board", row, col))?
+        .status;
+      match status {
+        // ships are only sunk by firing at them
+        Status::Live | Status::Kill => {
+          return Err(format!("a tracking board can't be marked {:#}", status))
+        }
+        _ if current != Status::Space && current != *status => {
+          return Err(format!("cell {}.{} is already {:#}", row, col, current))
+        }
+        _ => {}
+      }
+    }
+    for ((row, col), status) in entries {
+      self.positions[*row][*col].status = *status;
+    }
+    Ok(())
+  }
+
+  /// top left and bottom right cells around the only ship still afloat, none while more are left,
+  /// a cell wider than the ship on every side the board allows so its cells aren't given away
+  pub fn last_ship_region(&self) -> Option<(Coord, Coord)> {
+    let [ship] = self.ships_alive()[..] else {
+      return None;
+    };
+    let cells = self.pos_by_ship(ship.id.clone());
+    let rows = cells.iter().map(|p| p.coordinate.0);
+    let cols = cells.iter().map(|p| p.coordinate.1);
+    let top_left = Coord::new(
+      rows.clone().min()?.saturating_sub(1),
+      cols.clone().min()?.saturating_sub(1),
+    )?;
+    let bottom_right = Coord::new(
+      (rows.max()? + 1).min(ROWS - 1),
+      (cols.max()? + 1).min(COLS - 1),
+    )?;
+    Some((top_left, bottom_right))
+  }
+}
+
+/// how a cell next to a shot could have informed it
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Clue {
+  /// not fired at yet
+  Open,
+  Miss,
+  /// part of a ship not sunk yet, the rest of it is close
+  Hit,
+  /// part of a sunk ship
+  Wreck,
+}
+
+/// what `Board::cell_info` finds at a cell
+#[derive(PartialEq, Debug, Clone)]
+pub struct CellInfo {
+  pub status: Status,
+  pub ship: Option<ShipHealth>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct ShipHealth {
+  pub ship_type: ShipType,
+  /// cells not hit yet
+  pub cells_afloat: usize,
+  pub alive: bool,
+}
+
+impl Display for Board {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let s = self.as_grid().join("\n");
+    write!(f, "{}", s)
+  }
+}
+
+#[derive(PartialEq, Clone)]
+pub struct Position {
+  status: Status,
+  coordinate: Coordinate,
+  ship_id: Option<String>,
+  /// shots the armor of a live ship cell took so far
+  hits: usize,
+}
+
+impl Position {
+  fn new(coordinate: Coordinate) -> Self {
+    Self {
+      coordinate,
+      status: Status::Space,
+      ship_id: None,
+      hits: 0,
+    }
+  }
+
+  pub fn get_status(&self, ship: Option<&Ship>) -> Status {
+    if ship.is_some() && !ship.unwrap().alive {
+      Status::Kill
+    } else {
+      self.status
+    }
+  }
+}
+
+impl Display for Position {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.status)
+  }
+}
+
+#[derive(PartialEq, Clone)]
+pub struct Ship {
+  id: String,
+  rotation: u16,
+  alive: bool,
+  ship_type: ShipType,
+}
+
+impl Ship {
+  fn new<R: Rng>(ship_type: ShipType, rng: &mut R) -> Self {
+    let rotation = ROTATIONS.choose(rng).map_or(0, |r| *r);
+    Self {
+      id: ship_id(rng),
+      rotation,
+      alive: true,
+      ship_type,
+    }
+  }
+
+  fn shape(&self) -> ShipShape {
+    self.ship_type.get_shape(self.rotation)
+  }
+
+  fn footprint(&self) -> usize {
+    self.ship_type.cell_count()
+  }
+
+  /// live cells of the rotated shape relative to its start
+  fn offsets(&self) -> Vec<Coordinate> {
+    self
+      .shape()
+      .iter()
+      .enumerate()
+      .flat_map(|(x, row)| {
+        row
+          .iter()
+          .enumerate()
+          .filter(|(_, s)| **s == Status::Live)
+          .map(move |(y, _)| (x, y))
+      })
+      .collect()
+  }
+
+  /// start the ship was drawn at, derived from its cells
+  fn anchor(&self, cells: &[&Position]) -> Option<Anchor> {
+    let offsets = self.offsets();
+    let min_row = cells.iter().map(|p| p.coordinate.0).min()?;
+    let min_col = cells.iter().map(|p| p.coordinate.1).min()?;
+    let offset_row = offsets.iter().map(|o| o.0).min()?;
+    let offset_col = offsets.iter().map(|o| o.1).min()?;
+    Some((
+      min_row as isize - offset_row as isize,
+      min_col as isize - offset_col as isize,
+    ))
+  }
+
+  fn is_overlapping(&self, positions: &[Vec<Position>], start_cord: Anchor) -> bool {
+    let mut ship_found = false;
+    for (x, row) in self.shape().iter().enumerate() {
+      for y in 0..row.len() {
+        // the parts of the box off the board can't hold a ship
+        let row = usize::try_from(start_cord.0 + x as isize).ok();
+        let col = usize::try_from(start_cord.1 + y as isize).ok();
+        if row
+          .zip(col)
+          .and_then(|(row, col)| positions.get(row)?.get(col))
+          .is_some_and(|p| p.status == Status::Live)
+        {
+          ship_found = true;
+        }
+      }
+    }
+    ship_found
+  }
+
+  /// whether any of the ship's cells would be next to another ship's
+  fn is_touching(&self, positions: &[Vec<Position>], start_cord: Anchor) -> bool {
+    self.live_cells(start_cord).is_some_and(|cells| {
+      cells
+        .into_iter()
+        .flat_map(adjacent_coordinates)
+        .any(|(x, y)| positions[x][y].status == Status::Live)
+    })
+  }
+
+  /// board cells of the rotated shape's live cells, none if any of them is off the board
+  fn live_cells(&self, start_cord: Anchor) -> Option<Vec<Coordinate>> {
+    self
+      .offsets()
+      .into_iter()
+      .map(|(x, y)| {
+        let row = usize::try_from(start_cord.0 + x as isize).ok()?;
+        let col = usize::try_from(start_cord.1 + y as isize).ok()?;
+        Coord::new(row, col).map(Coordinate::from)
+      })
+      .collect()
+  }
+
+  fn erase(&self, positions: &mut [Vec<Position>]) {
+    positions
+      .iter_mut()
+      .flat_map(|row| row.iter_mut())
+      .filter(|p| p.ship_id.as_ref() == Some(&self.id))
+      .for_each(|p| {
+        p.status = Status::Space;
+        p.ship_id = None;
+      });
+  }
+
+  /// draws the ship only when all of its live cells land on the board
+  fn draw(&self, positions: &mut [Vec<Position>], start_cord: Anchor) -> bool {
+    let cells = match self.live_cells(start_cord) {
+      Some(cells) if positions.len() == ROWS && positions.iter().all(|r| r.len() == COLS) => cells,
+      _ => return false,
+    };
+    for (x, y) in cells.iter() {
+      positions[*x][*y].status = Status::Live;
+      positions[*x][*y].ship_id = Some(self.id.to_owned());
+    }
+    !cells.is_empty()
+  }
+}
+
+/// the kinds of ships, named after the letter their cells form
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShipType {
+  X,
+  V,
+  H,
+  I,
+  /// a single cell, only in custom fleets
+  O,
+  /// a diagonal line, only in custom fleets
+  D,
+}
+
+impl ShipType {
+  fn get_shape(&self, rotation: u16) -> ShipShape {
+    let shape = match *self {
+      ShipType::X => [
+        [Status::Live, Status::Space, Status::Live],
+        [Status::Space, Status::Live, Status::Space],
+        [Status::Live, Status::Space, Status::Live],
+      ],
+      ShipType::V => [
+        [Status::Live, Status::Space, Status::Live],
+        [Status::Live, Status::Space, Status::Live],
+        [Status::Space, Status::Live, Status::Space],
+      ],
+      ShipType::H => [
+        [Status::Live, Status::Space, Status::Live],
+        [Status::Live, Status::Live, Status::Live],
+        [Status::Live, Status::Space, Status::Live],
+      ],
+      ShipType::I => [
+        [Status::Space, Status::Live, Status::Space],
+        [Status::Space, Status::Live, Status::Space],
+        [Status::Space, Status::Live, Status::Space],
+      ],
+      ShipType::O => [
+        [Status::Space, Status::Space, Status::Space],
+        [Status::Space, Status::Live, Status::Space],
+        [Status::Space, Status::Space, Status::Space],
+      ],
+      ShipType::D => [
+        [Status::Live, Status::Space, Status::Space],
+        [Status::Space, Status::Live, Status::Space],
+        [Status::Space, Status::Space, Status::Live],
+      ],
+    };
+
+    match rotation {
+      180 => reverse_cols_of_rows(transpose(shape)),
+      270 => reverse_rows_of_cols(reverse_cols_of_rows(shape)),
+      360 => reverse_rows_of_cols(transpose(shape)),
+      _ => shape,
+    }
+  }
+
+  /// as many ships of this one type as the initial fleet has
+  pub fn uniform_fleet(&self) -> Vec<ShipType> {
+    vec![self.clone(); ShipType::get_initial_ships().len()]
+  }
+
+  /// whether some cells only touch the rest of the ship at a corner, like the X
+  fn is_diagonal(&self) -> bool {
+    let shape = self.get_shape(ROTATIONS[0]);
+    let live = |(row, col): Coordinate| shape[row][col] == Status::Live;
+    let cells = (0..SHIP_SIZE)
+      .flat_map(|row| (0..SHIP_SIZE).map(move |col| (row, col)))
+      .filter(|c| live(*c))
+      .collect::<Vec<_>>();
+    // flood fill from one cell through edge neighbours only
+    let mut reached = vec![cells[0]];
+    let mut i = 0;
+    while let Some(&(row, col)) = reached.get(i) {
+      let neighbours = [
+        (row.wrapping_sub(1), col),
+        (row + 1, col),
+        (row, col.wrapping_sub(1)),
+        (row, col + 1),
+      ];
+      for n in neighbours {
+        if cells.contains(&n) && !reached.contains(&n) {
+          reached.push(n);
+        }
+      }
+      i += 1;
+    }
+    reached.len() < cells.len()
+  }
+
+  /// the fleet with every diagonal ship swapped for a straight line, the same number of ships
+  pub fn without_diagonals(fleet: &[ShipType]) -> Vec<ShipType> {
+    fleet
+      .iter()
+      .map(|t| {
+        if t.is_diagonal() {
+          ShipType::I
+        } else {
+          t.clone()
+        }
+      })
+      .collect()
+  }
+
+  /// the fleet every board starts with
+  pub fn get_initial_ships() -> [ShipType; 4] {
+    [Self::X, Self::V, Self::H, Self::I]
+  }
+
+  pub fn name(&self) -> &'static str {
+    match *self {
+      ShipType::X => "Cross",
+      ShipType::V => "Chevron",
+      ShipType::H => "Ladder",
+      ShipType::I => "Line",
+      ShipType::O => "Dot",
+      ShipType::D => "Diagonal",
+    }
+  }
+
+  /// number of cells the ship covers, the same in every rotation
+  pub fn cell_count(&self) -> usize {
+    self
+      .get_shape(ROTATIONS[0])
+      .iter()
+      .flatten()
+      .filter(|s| **s == Status::Live)
+      .count()
+  }
+
+  fn code(&self) -> &'static str {
+    match *self {
+      ShipType::X => "X",
+      ShipType::V => "V",
+      ShipType::H => "H",
+      ShipType::I => "I",
+      ShipType::O => "O",
+      ShipType::D => "D",
+    }
+  }
+
+  fn from_code(code: &str) -> Result<Self, String> {
+    match code {
+      "X" => Ok(ShipType::X),
+      "V" => Ok(ShipType::V),
+      "H" => Ok(ShipType::H),
+      "I" => Ok(ShipType::I),
+      "O" => Ok(ShipType::O),
+      "D" => Ok(ShipType::D),
+      _ => Err(format!("unknown ship type {}", code)),
+    }
+  }
+}
+
+impl FromStr for ShipType {
+  type Err = String;
+
+  /// the one letter code, like `I`
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    ShipType::from_code(s)
+  }
+}
+
+/// e.g. "hit X x2, sunk V, missed x3", ships in order of the first cell fired at
+fn ship_breakdown(response: &FiringResponse) -> String {
+  let mut ships: Vec<(&str, &ShipType, usize, bool)> = vec![];
+  let mut miss_count = 0;
+  for result in response.values() {
+    match &result.ship {
+      Some((id, ship_type)) if result.status != Status::Miss => {
+        let i = match ships.iter().position(|s| s.0 == id) {
+          Some(i) => i,
+          None => {
+            ships.push((id, ship_type, 0, false));
+            ships.len() - 1
+          }
+        };
+        match result.status {
+          Status::Kill => ships[i].3 = true,
+          _ => ships[i].2 += 1,
+        }
+      }
+      _ => miss_count += 1,
+    }
+  }
+  ships
+    .iter()
+    .map(|(_, ship_type, hits, sunk)| match (hits, sunk) {
+      (_, true) => format!("sunk {}", ship_type.code()),
+      (1, false) => format!("hit {}", ship_type.code()),
+      _ => format!("hit {} x{}", ship_type.code(), hits),
+    })
+    .chain(match miss_count {
+      0 => None,
+      1 => Some("missed".to_string()),
+      n => Some(format!("missed x{}", n)),
+    })
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// every anchor and rotation keeping the ship on the board, legal or not, one for each set of cells
+/// it can cover
+fn placement_candidates(ship_type: &ShipType) -> Vec<(Anchor, u16)> {
+  let mut ship = Ship {
+    id: String::default(),
+    rotation: 0,
+    alive: true,
+    ship_type: ship_type.clone(),
+  };
+  // the box may stick out over the top or left edge as long as the ship doesn't
+  let reach = 1 - SHIP_SIZE as isize;
+  let mut shapes = vec![];
+  let mut candidates = vec![];
+  for rotation in ROTATIONS.iter() {
+    ship.rotation = *rotation;
+    // a rotation drawing a shape seen before only shifts the same footprints
+    let offsets = ship.offsets();
+    let top = offsets.iter().map(|o| o.0).min().unwrap_or(0);
+    let left = offsets.iter().map(|o| o.1).min().unwrap_or(0);
+    let shape = offsets
+      .iter()
+      .map(|(x, y)| (x - top, y - left))
+      .collect::<Vec<_>>();
+    if shapes.contains(&shape) {
+      continue;
+    }
+    shapes.push(shape);
+    for x in reach..ROWS as isize {
+      for y in reach..COLS as isize {
+        if ship.live_cells((x, y)).is_some() {
+          candidates.push(((x, y), *rotation));
+        }
+      }
+    }
+  }
+  candidates
+}
+
+/// the ship can be drawn at `start` without overlapping, or touching when ships must not touch
+fn is_legal_placement(
+  positions: &[Vec<Position>],
+  ship: &Ship,
+  start: Anchor,
+  no_touching: bool,
+) -> bool {
+  // anchors near the edges are fine as long as the live cells stay on the board
+  ship.live_cells(start).is_some()
+    && !ship.is_overlapping(positions, start)
+    && !(no_touching && ship.is_touching(positions, start))
+}
+
+/// place ships in order at random positions and rotations, backtracking when a later ship can't fit
+fn place_fleet<R: Rng>(
+  positions: &mut [Vec<Position>],
+  ship_types: &[ShipType],
+  no_touching: bool,
+  rng: &mut R,
+) -> Option<Vec<Ship>> {
+  let (ship_type, rest) = match ship_types.split_first() {
+    Some(split) => split,
+    None => return Some(vec![]),
+  };
+
+  let mut candidates = placement_candidates(ship_type);
+  candidates.shuffle(rng);
+
+  let mut ship = Ship::new(ship_type.clone(), rng);
+  for (start_cords, rotation) in candidates {
+    ship.rotation = rotation;
+    if !is_legal_placement(positions, &ship, start_cords, no_touching) {
+      continue;
+    }
+    ship.draw(positions, start_cords);
+    if let Some(mut ships) = place_fleet(positions, rest, no_touching, rng) {
+      ships.insert(0, ship);
+      return Some(ships);
+    }
+    ship.erase(positions);
+  }
+  None
+}
+
+/// `coord` and the cells around it, diagonals included
+fn adjacent_coordinates(coord: Coordinate) -> impl Iterator<Item = Coordinate> {
+  near_coordinates(coord, 1)
+}
+
+/// coordinates on the board at most `jitter` rows and columns away from `coord`
+fn near_coordinates(coord: Coordinate, jitter: usize) -> impl Iterator<Item = Coordinate> {
+  (coord.0.saturating_sub(jitter)..=(coord.0 + jitter).min(ROWS - 1)).flat_map(move |x| {
+    (coord.1.saturating_sub(jitter)..=(coord.1 + jitter).min(COLS - 1)).map(move |y| (x, y))
+  })
+}
+
+/// a v4 uuid from `rng`, so a seed gives the same ship ids too
+fn ship_id<R: Rng>(rng: &mut R) -> String {
+  Builder::from_bytes(rng.gen())
+    .set_variant(Variant::RFC4122)
+    .set_version(Version::Random)
+    .build()
+    .to_string()
+}
+
+/**
+ * transpose a 2D char array.
+ */
+fn transpose(inp: ShipShape) -> ShipShape {
+  if inp.is_empty() {
+    //empty or unset array, nothing do to here
+    return inp;
+  }
+
+  let mut out = inp;
+
+  for (x, cols) in inp.iter().enumerate() {
+    for (y, _) in cols.iter().enumerate() {
+      out[y][x] = inp[x][y];
+    }
+  }
+  out
+}
+
+/**
+ * reverse columns of each rows in a 2d array.
+ */
+fn reverse_cols_of_rows(inp: ShipShape) -> ShipShape {
+  if inp.is_empty() {
+    //empty or unset array, nothing do to here
+    return inp;
+  }
+  let mut out = inp;
+
+  for (x, cols) in inp.iter().enumerate() {
+    for (y, _) in cols.iter().enumerate() {
+      out[x][cols.len() - y - 1] = inp[x][y];
+    }
+  }
+  out
+}
+
+/**
+ * reverse rows of each column in a 2d array.
+ */
+fn reverse_rows_of_cols(inp: ShipShape) -> ShipShape {
+  if inp.is_empty() {
+    //empty or unset array, nothing do to here
+    return inp;
+  }
+
+  let mut out = inp;
+
+  for (x, cols) in inp.iter().enumerate() {
+    for (y, _) in cols.iter().enumerate() {
+      out[inp.len() - x - 1][y] = inp[x][y];
+    }
+  }
+  out
+}
 
 #[cfg(test)]
 mod tests {
   use super::*;
+
+  fn coords<'a>(shots: impl IntoIterator<Item = &'a Coordinate>) -> BTreeSet<Coord> {
+    shots
+      .into_iter()
+      .map(|c| Coord::try_from(*c).unwrap())
+      .collect()
+  }
+
+  /// one ascii char per status, readable in diffs whatever the emoji
+  fn ascii(status: Status) -> char {
+    match status {
+      Status::Live => '#',
+      Status::Miss => 'o',
+      Status::Hit => 'x',
+      Status::Kill => '*',
+      Status::Space => '.',
+    }
+  }
+
+  /// compare every row of the grid with a pattern of `ascii` chars
+  fn assert_grid_eq(positions: &[Vec<Position>], expected: &[&str]) {
+    let grid = positions
+      .iter()
+      .map(|row| row.iter().map(|p| ascii(p.status)).collect::<String>())
+      .collect::<Vec<_>>();
+    assert_eq!(grid, expected, "\n{}", grid.join("\n"));
+  }
+
+  #[test]
+  fn test_coord_new() {
+    assert_eq!(Coordinate::from(Coord::new(0, 0).unwrap()), (0, 0));
+    assert_eq!(
+      Coordinate::from(Coord::new(ROWS - 1, COLS - 1).unwrap()),
+      (ROWS - 1, COLS - 1)
+    );
+    assert_eq!(Coord::new(ROWS, 0), None);
+    assert_eq!(Coord::new(0, COLS), None);
+    assert_eq!(Coord::new(usize::MAX, usize::MAX), None);
+
+    assert_eq!(Coord::try_from((3, 4)), Ok(Coord::new(3, 4).unwrap()));
+    assert_eq!(Coord::try_from((3, COLS)), Err((3, COLS)));
+  }
+  #[test]
+  fn test_game_is_valid_rule() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy);
+    assert!(game.is_valid_rule(0));
+    assert!(!game.is_valid_rule(1));
+
+    game.rule = Rule::Fury;
+
+    assert!(game.is_valid_rule(0));
+    assert!(game.is_valid_rule(3));
+    assert!(!game.is_valid_rule(4));
+
+    game.rule = Rule::Charge;
+
+    assert!(game.is_valid_rule(0));
+    assert!(!game.is_valid_rule(1));
+  }
+
+  #[test]
+  fn test_game_charge_allowance() {
+    // both sides get one shot plus one per opponent ship they sank
+    for turn in 0..2 {
+      let mut game = Game::with_seed(Rule::Charge, Difficulty::Easy, 1);
+      game.turn = turn;
+      let fleet = game.players[1 - turn].boards[0].ships.len();
+      for sunk in [0, 1, fleet - 1] {
+        game.players[1 - turn].boards[0]
+          .ships
+          .iter_mut()
+          .enumerate()
+          .for_each(|(i, ship)| ship.alive = i >= sunk);
+        assert_eq!(game.max_shots(), sunk + 1, "turn {} sunk {}", turn, sunk);
+        assert!(game.is_valid_rule(sunk));
+        assert!(!game.is_valid_rule(sunk + 1));
+        assert_eq!(game.generate_bot_firing_coordinates().len(), sunk + 1);
+      }
+    }
+  }
+
+  #[test]
+  fn test_game_bot_memory() {
+    let mut game = Game::with_seed(Rule::Barrage, Difficulty::Hard, 1);
+    game.bot_memory = Some(0);
+    game.barrage_shots = 7;
+    game.turn = 1;
+    let mut fired = BTreeSet::new();
+    while !game.is_won() {
+      let shots = game.generate_bot_firing_coordinates();
+      // the hard computer forgets its hits, yet never fires at a cell twice
+      assert!(shots.iter().all(|c| fired.insert(*c)));
+      let shots = shots
+        .into_iter()
+        .map(|c| Coord::try_from(c).unwrap())
+        .collect();
+      game.fire(&shots, true);
+      game.turn = 1;
+    }
+    assert!(fired.len() <= ROWS * COLS);
+
+    // forgetting keeps only the remembered shots on the tracking board
+    let board = game.computer().opponent_board();
+    let remembered = BTreeSet::from([*fired.iter().next().unwrap()]);
+    assert_eq!(board.forgetting(&remembered).fired_positions().len(), 1);
+  }
+
+  struct CornerStrategy;
+
+  impl FiringStrategy for CornerStrategy {
+    fn choose_shots(&self, _: &Board, _: usize, _: &mut StdRng) -> BTreeSet<Coordinate> {
+      let mut shots = BTreeSet::new();
+      shots.insert((0, 0));
+      shots
+    }
+  }
+
+  #[test]
+  fn test_game_custom_strategy() {
+    let mut game = Game::new(Rule::Fury, Difficulty::Hard);
+    game.strategy = Box::new(CornerStrategy);
+    game.turn = 1;
+
+    game.bot_fire();
+    assert_eq!(game.history().len(), 1);
+    assert_eq!(game.history()[0].shots.len(), 1);
+    assert!(game.history()[0].shots.contains(&Coord::new(0, 0).unwrap()));
+    assert_ne!(
+      game.computer().opponent_board().positions[0][0].status,
+      Status::Space
+    );
+  }
+
+  #[test]
+  fn test_game_barrage() {
+    let mut game = Game::new(Rule::Barrage, Difficulty::Hard);
+    assert_eq!(game.barrage_shots, 3);
+
+    assert!(game.is_valid_rule(2));
+    assert!(!game.is_valid_rule(3));
+    assert_eq!(game.generate_bot_firing_coordinates().len(), 3);
+
+    game.barrage_shots = 5;
+    assert!(game.is_valid_rule(4));
+    assert!(!game.is_valid_rule(5));
+    assert_eq!(game.generate_bot_firing_coordinates().len(), 5);
+  }
+
+  #[test]
+  fn test_rule_description() {
+    Rule::variants().iter().for_each(|v| {
+      let rule = v.parse::<Rule>().unwrap();
+      assert!(!rule.description().is_empty(), "{} has no description", v);
+    });
+  }
+
+  #[test]
+  fn test_game_shots_left() {
+    let mut game = Game::new(Rule::Fury, Difficulty::Easy);
+    assert_eq!(game.shots_left(0), 4);
+    assert_eq!(game.shots_left(1), 3);
+
+    // each of your ships sunk takes away a shot
+    game.players[0].boards[0].ships[0].alive = false;
+    game.players[0].boards[0].ships[2].alive = false;
+    assert_eq!(game.shots_left(0), 2);
+    assert_eq!(game.shots_left(1), 1);
+    assert_eq!(game.shots_left(3), 0);
+
+    game.rule = Rule::Charge;
+    assert_eq!(game.shots_left(0), 1);
+    game.players[1].boards[0].ships[1].alive = false;
+    assert_eq!(game.shots_left(0), 2);
+  }
+
+  #[test]
+  fn test_game_fire() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy);
+
+    let mut shots = BTreeSet::new();
+    shots.insert((1, 1));
+    shots.insert((3, 3));
+
+    let msg = game.fire(&coords(&shots), false);
+
+    assert!(!msg.is_empty());
+    assert!(!game.is_user_turn());
+    assert!(game.outcome.is_none());
+  }
+
+  #[test]
+  fn test_game_undo() {
+    let mut game = Game::new(Rule::Fury, Difficulty::Easy);
+
+    let mut shots = BTreeSet::new();
+    shots.insert((1, 1));
+    game.fire(&coords(&shots), false);
+    // undo is disabled outside practice
+    assert!(!game.undo());
+
+    game.practice = true;
+    game.turn = 0;
+    let players = game.players.clone();
+
+    // sink a ship and miss elsewhere in one volley
+    let ship_id = game.computer().player_board().ships[0].id.clone();
+    let mut shots = game
+      .computer()
+      .player_board()
+      .pos_by_ship(ship_id)
+      .iter()
+      .map(|p| p.coordinate)
+      .collect::<BTreeSet<_>>();
+    shots.insert((1, 1));
+    game.fire(&coords(&shots), false);
+    assert!(!game.is_user_turn());
+    assert!(game.players != players);
+
+    assert!(game.undo());
+    assert!(game.is_user_turn());
+    assert!(game.players == players);
+    assert_eq!(game.computer().player_board().ships_alive().len(), 4);
+    assert!(!game.undo());
+  }
+
+  #[test]
+  fn test_diff_replays() {
+    let volley = |turn, row, col| Volley {
+      turn,
+      shots: BTreeSet::from([Coord::new(row, col).unwrap()]),
+    };
+    let a = vec![volley(0, 1, 1), volley(1, 2, 2), volley(0, 3, 3)];
+    assert_eq!(diff_replays(&a, &a.clone()), None);
+
+    let mut b = a.clone();
+    b[1] = volley(1, 2, 3);
+    assert_eq!(diff_replays(&a, &b), Some(1));
+    assert_eq!(diff_replays(&a, &a[..2]), Some(2));
+  }
+
+  #[test]
+  fn test_game_state_at() {
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Hard, 7);
+    let initial_players = game.players.clone();
+    while !game.is_won() {
+      game.bot_fire();
+    }
+    let len = game.history().len();
+
+    assert!(game.state_at(0).players == initial_players);
+    let final_state = game.state_at(len);
+    assert!(final_state.players == game.players);
+    assert_eq!(final_state.outcome, game.outcome);
+    // past the end is clamped to the final state
+    assert!(game.state_at(len + 5).players == game.players);
+  }
+
+  #[test]
+  fn test_game_fire_volley_sinks_last_ships() {
+    let mut game = Game::new(Rule::Fury, Difficulty::Easy);
+    let ship_cells = |game: &Game, index: usize| {
+      let id = game.computer().player_board().ships[index].id.clone();
+      game
+        .computer()
+        .player_board()
+        .pos_by_ship(id)
+        .iter()
+        .map(|p| p.coordinate)
+        .collect::<BTreeSet<_>>()
+    };
+
+    for index in 0..2 {
+      let shots = ship_cells(&game, index);
+      game.turn = 0;
+      assert_ne!(game.fire(&coords(&shots), false), "You won 🙌");
+    }
+
+    // hit one cell of each remaining ship first, then sink both in a single volley
+    let last_ships = ship_cells(&game, 2)
+      .into_iter()
+      .chain(ship_cells(&game, 3))
+      .collect::<BTreeSet<_>>();
+    let first_hits = [
+      *ship_cells(&game, 2).iter().next_back().unwrap(),
+      *ship_cells(&game, 3).iter().next_back().unwrap(),
+    ]
+    .iter()
+    .copied()
+    .collect::<BTreeSet<_>>();
+    game.turn = 0;
+    game.fire(&coords(&first_hits), false);
+    assert!(!game.is_won());
+
+    game.turn = 0;
+    let msg = game.fire(&coords(&last_ships), false);
+    assert_eq!(msg, "You won 🙌");
+    assert_eq!(game.winner(), Some(0));
+    assert!(game.computer().player_board().ships_alive().is_empty());
+    // every cell of the sunk ships shows as killed, including the re-fired ones
+    last_ships.iter().for_each(|c| {
+      assert_eq!(
+        game.player().opponent_board().positions[c.0][c.1].status,
+        Status::Kill
+      )
+    });
+  }
+
+  /// sinks the computer's first ship, all but one cell first and then the last one on its own,
+  /// and returns the ship's cells with the last one split off
+  fn sink_first_ship(game: &mut Game) -> (Coordinate, Vec<Coordinate>) {
+    let id = game.computer().player_board().ships[0].id.clone();
+    let cells = game
+      .computer()
+      .player_board()
+      .pos_by_ship(id)
+      .iter()
+      .map(|p| p.coordinate)
+      .collect::<Vec<_>>();
+    let (last, rest) = cells.split_last().unwrap();
+    game.fire(&coords(rest), false);
+    game.turn = 0;
+    game.fire(&coords(&[*last]), false);
+    (*last, rest.to_vec())
+  }
+
+  #[test]
+  fn test_game_fire_reveal_sunk_ship() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy);
+    // a single hit on the last alive cell shows the whole ship as sunk
+    let (last, rest) = sink_first_ship(&mut game);
+    let tracking = game.player().opponent_board();
+    assert_eq!(tracking.positions[last.0][last.1].status, Status::Kill);
+    rest
+      .iter()
+      .for_each(|c| assert_eq!(tracking.positions[c.0][c.1].status, Status::Kill));
+  }
+
+  #[test]
+  fn test_game_fire_reveal_full_ship_on_kill() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy);
+    game.reveal_full_ship_on_kill = false;
+    let (last, rest) = sink_first_ship(&mut game);
+    let tracking = game.player().opponent_board();
+    assert_eq!(tracking.positions[last.0][last.1].status, Status::Kill);
+    rest
+      .iter()
+      .for_each(|c| assert_eq!(tracking.positions[c.0][c.1].status, Status::Hit));
+
+    // sunk in a single volley the fog still shows one cell of it as sunk
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Easy, 1);
+    game.reveal_full_ship_on_kill = false;
+    let id = game.computer().player_board().ships[0].id.clone();
+    let cells = game
+      .computer()
+      .player_board()
+      .pos_by_ship(id)
+      .iter()
+      .map(|p| p.coordinate)
+      .collect::<Vec<_>>();
+    game.fire(&coords(&cells), false);
+    let tracking = game.player().opponent_board();
+    let statuses = cells
+      .iter()
+      .map(|c| tracking.positions[c.0][c.1].status)
+      .collect::<Vec<_>>();
+    assert_eq!(statuses.iter().filter(|s| **s == Status::Kill).count(), 1);
+    assert_eq!(
+      statuses.iter().filter(|s| **s == Status::Hit).count(),
+      cells.len() - 1
+    );
+  }
+
+  #[test]
+  fn test_game_fire_win_threshold() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy);
+    game.win_threshold = Some(2);
+
+    let ship_ids = game.computer().player_board().ships[..2]
+      .iter()
+      .map(|s| s.id.clone())
+      .collect::<Vec<_>>();
+
+    for (i, id) in ship_ids.into_iter().enumerate() {
+      let shots = game
+        .computer()
+        .player_board()
+        .pos_by_ship(id)
+        .iter()
+        .map(|p| p.coordinate)
+        .collect::<BTreeSet<_>>();
+      game.turn = 0;
+      game.fire(&coords(&shots), false);
+      assert_eq!(game.is_won(), i == 1);
+    }
+
+    // game is over while ships are still afloat
+    assert_eq!(game.winner(), Some(0));
+    assert!(!game.computer().player_board().ships_alive().is_empty());
+  }
+
+  #[test]
+  fn test_game_generate_firing_coordinates() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy);
+
+    let shots = game.generate_bot_firing_coordinates();
+    assert_eq!(shots.len(), 1);
+
+    let mut game = Game::new(Rule::Charge, Difficulty::Easy);
+
+    let shots = game.generate_bot_firing_coordinates();
+    assert_eq!(shots.len(), 1);
+
+    let mut game = Game::new(Rule::Fury, Difficulty::Easy);
+
+    let shots = game.generate_bot_firing_coordinates();
+    assert_eq!(shots.len(), 4);
+  }
+
+  #[test]
+  fn test_status_display_width() {
+    assert_eq!(Status::Live.display_width(), 2);
+    assert_eq!(Status::Miss.display_width(), 2);
+    assert_eq!(Status::Hit.display_width(), 2);
+    assert_eq!(Status::Kill.display_width(), 2);
+    assert_eq!(Status::Space.display_width(), 1);
+    assert_eq!(Status::Kill.label_width(true), 4);
+    assert_eq!(Status::Space.label_width(true), 1);
+  }
+
+  #[test]
+  fn test_status_label() {
+    assert_eq!(Status::Hit.label(false), "💥");
+    assert_eq!(Status::Hit.label(true), "hit");
+    assert_eq!(Status::Miss.label(true), "miss");
+    assert_eq!(Status::Kill.label(true), "sunk");
+    assert_eq!(Status::Space.label(true), "water");
+    assert_eq!(format!("{:#}", Status::Live), "ship");
+  }
+
+  #[test]
+  fn test_status_from_str() {
+    for status in &[
+      Status::Live,
+      Status::Miss,
+      Status::Hit,
+      Status::Kill,
+      Status::Space,
+    ] {
+      assert_eq!(status.to_string().parse::<Status>(), Ok(*status));
+      assert_eq!(format!("{:#}", status).parse::<Status>(), Ok(*status));
+    }
+    assert!("boom".parse::<Status>().is_err());
+    assert!("".parse::<Status>().is_err());
+  }
+
+  #[test]
+  fn test_easy_bot_never_repeats_a_shot() {
+    for (seed, rule) in [Rule::Default, Rule::Fury, Rule::Charge, Rule::Barrage]
+      .iter()
+      .enumerate()
+    {
+      let mut game = Game::with_seed(*rule, Difficulty::Easy, seed as u64);
+      // the computer plays both sides until someone wins
+      while !game.is_won() {
+        game.bot_fire();
+      }
+      for turn in 0..2 {
+        let shots = game
+          .history()
+          .iter()
+          .filter(|v| v.turn == turn)
+          .flat_map(|v| v.shots.iter())
+          .collect::<Vec<_>>();
+        let unique = shots.iter().collect::<BTreeSet<_>>();
+        assert_eq!(unique.len(), shots.len(), "{} repeated a shot", rule);
+      }
+    }
+  }
+
+  #[test]
+  fn test_adaptive_strategy() {
+    let mut strategy = AdaptiveStrategy::default();
+    assert_eq!(strategy.targeting, 0.0);
+    strategy.observe_accuracy(0.1);
+    let low = strategy.targeting;
+    strategy.observe_accuracy(0.4);
+    assert!(strategy.targeting > low);
+    strategy.observe_accuracy(0.9);
+    assert_eq!(strategy.targeting, 1.0);
+
+    // the player's accuracy feeds the computer, and the same seed plays the same game
+    let play = || {
+      let mut game = Game::with_seed(Rule::Default, Difficulty::Adaptive, 3);
+      let ship_cells = game.computer().player_board().ship_cells();
+      game.fire(&coords(&ship_cells[..2]), false);
+      assert_eq!(game.accuracy(0), 1.0);
+      while !game.is_won() {
+        game.bot_fire();
+      }
+      game.history().to_vec()
+    };
+    assert_eq!(play(), play());
+  }
+
+  #[test]
+  fn test_strategies_nearly_full_board() {
+    let mut board = Board::new(false, &mut rand::thread_rng());
+    board
+      .positions
+      .iter_mut()
+      .flat_map(|pr| pr.iter_mut())
+      .for_each(|p| p.status = Status::Miss);
+    board.positions[4][4].status = Status::Hit;
+    board.positions[0][0].status = Status::Space;
+    board.positions[9][9].status = Status::Space;
+
+    let mut rng = StdRng::seed_from_u64(1);
+    let strategies: [Box<dyn FiringStrategy>; 2] =
+      [Box::new(EasyStrategy), Box::new(HardStrategy::default())];
+    for strategy in strategies.iter() {
+      let shots = strategy.choose_shots(&board, 4, &mut rng);
+      assert_eq!(shots.len(), 2);
+      assert!(shots.contains(&(0, 0)) && shots.contains(&(9, 9)));
+    }
+  }
+
+  #[test]
+  fn test_hard_strategy_fair() {
+    // both sides fire with the same strategy, so count every shot of the game
+    let average_shots = |fair: bool| {
+      (0..40)
+        .map(|seed| {
+          let mut game = Game::with_seed(Rule::Default, Difficulty::Hard, seed);
+          game.strategy = Box::new(HardStrategy {
+            fair,
+            ..HardStrategy::default()
+          });
+          while game.winner().is_none() {
+            game.bot_fire();
+          }
+          game.history().iter().map(|v| v.shots.len()).sum::<usize>()
+        })
+        .sum::<usize>() as f64
+        / 40.0
+    };
+    assert!(average_shots(true) >= average_shots(false));
+
+    // it only fires next to the first hit
+    let mut board = Board::new(false, &mut StdRng::seed_from_u64(1));
+    board.positions[4][4].status = Status::Hit;
+    board.positions[8][8].status = Status::Hit;
+    let mut rng = StdRng::seed_from_u64(1);
+    for _ in 0..10 {
+      let shots = HardStrategy {
+        fair: true,
+        ..HardStrategy::default()
+      }
+      .choose_shots(&board, 4, &mut rng);
+      assert_eq!(
+        shots,
+        [(3, 4), (4, 3), (4, 5), (5, 4)].iter().copied().collect()
+      );
+    }
+  }
+
+  #[test]
+  fn test_hard_strategy_jitter() {
+    let mut board = Board::new(false, &mut StdRng::seed_from_u64(1));
+    board.positions[4][4].status = Status::Hit;
+    let strategy = HardStrategy {
+      jitter: 1,
+      ..HardStrategy::default()
+    };
+    let mut rng = StdRng::seed_from_u64(1);
+    for _ in 0..20 {
+      let shots = strategy.choose_shots(&board, 8, &mut rng);
+      assert_eq!(shots.len(), 8);
+      assert!(shots
+        .iter()
+        .all(|c| c.0.abs_diff(4) <= 1 && c.1.abs_diff(4) <= 1));
+    }
+    // the default window reaches two cells out
+    let shots = HardStrategy::default().choose_shots(&board, 24, &mut rng);
+    assert!(shots.contains(&(2, 2)) && shots.contains(&(6, 6)));
+  }
+
+  #[test]
+  fn test_hard_strategy_center_bias() {
+    let board = Board::new(false, &mut StdRng::seed_from_u64(1));
+    let density = board.placement_density();
+    assert!(density[4][4] > density[0][0]);
+
+    // average distance from the center of the first shots on an empty board
+    let spread = |center_bias: bool| {
+      let strategy = HardStrategy {
+        center_bias,
+        ..HardStrategy::default()
+      };
+      let shots = (0..20)
+        .flat_map(|seed| strategy.choose_shots(&board, 10, &mut StdRng::seed_from_u64(seed)))
+        .collect::<Vec<_>>();
+      assert_eq!(shots.len(), 200);
+      shots
+        .iter()
+        .map(|(x, y)| (*x as f64 - 4.5).abs() + (*y as f64 - 4.5).abs())
+        .sum::<f64>()
+        / shots.len() as f64
+    };
+    assert!(spread(true) < spread(false));
+  }
+
+  #[test]
+  fn test_board_move_ship() {
+    // an upright I at column 5 covers rows 2 to 4
+    let mut board = Board::from_code("I.90.2.4;O.90.7.7").unwrap();
+    let at = |row, col| Coord::new(row, col).unwrap();
+    let status = |board: &Board, row: usize, col: usize| board.positions[row][col].status;
+
+    board.move_ship(at(3, 5), at(7, 1), None).unwrap();
+    for row in 2..=4 {
+      assert_eq!(status(&board, row, 5), Status::Space);
+      assert!(board.positions[row][5].ship_id.is_none());
+    }
+    for row in 6..=8 {
+      assert_eq!(status(&board, row, 1), Status::Live);
+      assert_eq!(board.positions[row][1].ship_id.as_deref(), Some("0"));
+    }
+    assert_eq!(board.validate(), Ok(()));
+
+    // not onto another ship, off the board or where the opponent fired
+    assert!(board.move_ship(at(7, 1), at(7, 8), None).is_err());
+    assert!(board.move_ship(at(6, 1), at(9, 1), None).is_err());
+    board.positions[0][5].status = Status::Miss;
+    assert!(board.move_ship(at(6, 1), at(0, 5), None).is_err());
+    assert!(board.move_ship(at(6, 1), at(1, 5), None).is_ok());
+    assert!(board.move_ship(at(2, 5), at(7, 1), None).is_ok());
+    assert!(board.move_ship(at(0, 9), at(5, 5), None).is_err());
+
+    // damaged ships stay put
+    board.take_fire(&std::iter::once((7, 1)).collect(), true);
+    assert!(board.move_ship(at(6, 1), at(3, 5), None).is_err());
+    assert_eq!(status(&board, 6, 1), Status::Live);
+  }
+
+  #[test]
+  fn test_board_move_preview_rotation() {
+    // an upright I at column 5 covers rows 2 to 4
+    let mut board = Board::from_code("I.90.2.4;O.90.7.7").unwrap();
+    let at = |row, col| Coord::new(row, col).unwrap();
+
+    // upright against the right edge it fits, lying down it sticks out
+    let (cells, fits) = board.move_preview(at(3, 5), at(3, 9), None).unwrap();
+    assert_eq!(cells, vec![(2, 9), (3, 9), (4, 9)]);
+    assert!(fits);
+    let (cells, fits) = board.move_preview(at(3, 5), at(3, 9), Some(180)).unwrap();
+    assert_eq!(cells, vec![(3, 8), (3, 9)]);
+    assert!(!fits);
+    assert!(board.move_preview(at(3, 5), at(3, 9), Some(270)).unwrap().1);
+    assert!(board.move_ship(at(3, 5), at(3, 9), Some(180)).is_err());
+
+    // lying down in the middle it fits and the move keeps the rotation
+    assert!(board.move_preview(at(3, 5), at(3, 4), Some(180)).unwrap().1);
+    board.move_ship(at(3, 5), at(3, 4), Some(180)).unwrap();
+    assert_eq!(board.rotation_at(at(3, 4)), Some(180));
+    assert_eq!(board.validate(), Ok(()));
+    // the other ship is in the way
+    assert!(!board.move_preview(at(3, 4), at(8, 8), Some(90)).unwrap().1);
+  }
+
+  #[test]
+  fn test_game_maneuver() {
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    let cell = Coord::try_from(game.player().player_board().ship_cells()[0]).unwrap();
+    assert!(game.maneuver(cell, cell, None).is_err());
+
+    game.allow_maneuver = true;
+    game.bot_fire();
+    game.turn = 0;
+    let free = (0..ROWS * COLS)
+      .filter_map(|i| Coord::new(i / COLS, i % COLS))
+      .filter(|to| *to != cell)
+      .find(|to| {
+        let mut board = game.player().player_board().clone();
+        board.move_ship(cell, *to, None).is_ok()
+      })
+      .unwrap();
+    game.maneuver(cell, free, None).unwrap();
+    assert!(!game.is_user_turn());
+    assert!(game.player().player_board().cell_info(free).ship.is_some());
+    game.turn = 0;
+    assert!(!game.can_maneuver());
+    assert!(game.maneuver(free, cell, None).is_err());
+    game.turn = 1;
+    game.bot_fire();
+
+    // saves and replays move the ship at the same point
+    let board = game.player().player_board().to_string();
+    let loaded = Game::from_save(&game.to_save()).unwrap();
+    assert_eq!(loaded.player().player_board().to_string(), board);
+    let loaded = Game::from_save_bytes(&game.to_save_bytes()).unwrap();
+    assert_eq!(loaded.player().player_board().to_string(), board);
+    let replayed = game.state_at(game.history().len());
+    assert_eq!(replayed.player().player_board().to_string(), board);
+    assert!(replayed.players[0].maneuvered);
+  }
+
+  #[test]
+  fn test_board_scuttle() {
+    let mut board = Board::from_code("I.90.2.4;O.90.7.7").unwrap();
+    assert_eq!(board.ships_alive().len(), 2);
+    assert_eq!(board.scuttle("0".into()), Ok(ShipType::I));
+    assert_eq!(board.ships_alive().len(), 1);
+    assert!(board
+      .pos_by_ship("0".into())
+      .iter()
+      .all(|p| p.status == Status::Kill));
+    assert!(board.scuttle("0".into()).is_err());
+    assert!(board.scuttle("z".into()).is_err());
+  }
+
+  #[test]
+  fn test_board_apply_tracking() {
+    let mut board = Board::new(false, &mut StdRng::seed_from_u64(1));
+    let entries = [
+      ((1, 1), Status::Hit),
+      ((1, 2), Status::Hit),
+      ((4, 7), Status::Miss),
+      ((9, 0), Status::Miss),
+    ];
+    board.apply_tracking(&entries).unwrap();
+    for p in board.positions() {
+      let expected = entries
+        .iter()
+        .find(|(c, _)| *c == p.coordinate)
+        .map_or(Status::Space, |(_, s)| *s);
+      assert_eq!(p.status, expected);
+    }
+    assert!(board.ships_sunk().is_empty());
+
+    let before = board.clone();
+    for bad in [
+      ((0, 0), Status::Live),
+      ((0, 0), Status::Kill),
+      ((1, 1), Status::Miss),
+      ((ROWS, 0), Status::Hit),
+    ] {
+      assert!(board
+        .apply_tracking(&[((0, 1), Status::Miss), bad])
+        .is_err());
+    }
+    assert!(board == before);
+  }
+
+  #[test]
+  fn test_game_preseed_tracking() {
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    // a fixed fleet, so new games match the known hits too
+    game.set_computer_board(game.computer().player_board().clone());
+    game.set_reveal_count(2);
+    let fleet = game.computer().player_board();
+    let revealed = game.revealed_cells();
+    let ship = fleet
+      .ship_cells()
+      .into_iter()
+      .find(|c| !revealed.contains(c))
+      .unwrap();
+    let water = fleet
+      .positions()
+      .into_iter()
+      .find(|p| p.ship_id.is_none())
+      .unwrap()
+      .coordinate;
+    assert!(game.preseed_tracking(&[(water, Status::Hit)]).is_err());
+    let text = format!(
+      "{} hit\n{} miss\n",
+      Coord::try_from(ship).unwrap(),
+      Coord::try_from(water).unwrap()
+    );
+    game
+      .preseed_tracking(&parse_tracking(&text).unwrap())
+      .unwrap();
+
+    // saves keep them apart from the revealed cells, new games mark them again
+    let hits = |game: &Game| {
+      game
+        .player()
+        .opponent_board()
+        .positions()
+        .iter()
+        .filter(|p| p.status == Status::Hit)
+        .count()
+    };
+    game.reset();
+    for loaded in [
+      Game::from_save(&game.to_save()).unwrap(),
+      Game::from_save_bytes(&game.to_save_bytes()).unwrap(),
+      game,
+    ] {
+      let tracking = loaded.player().opponent_board();
+      assert_eq!(tracking.positions[ship.0][ship.1].status, Status::Hit);
+      assert_eq!(tracking.positions[water.0][water.1].status, Status::Miss);
+      assert_eq!(loaded.reveal_count, 2);
+      assert_eq!(hits(&loaded), 3);
+    }
+  }
+
+  #[test]
+  fn test_board_with_formation() {
+    for formation in [Formation::Spread, Formation::Cluster, Formation::Edges] {
+      let board = Board::with_formation(formation).unwrap();
+      let cells = ShipType::get_initial_ships()
+        .iter()
+        .map(ShipType::cell_count)
+        .sum::<usize>();
+      // no overlap, every ship cell is drawn
+      assert_eq!(board.ship_cells().len(), cells, "{}", formation);
+    }
+
+    let board = Board::with_formation(Formation::Edges).unwrap();
+    for ship in board.ships.iter() {
+      let cells = board.pos_by_ship(ship.id.clone());
+      let rows = cells.iter().map(|p| p.coordinate.0);
+      let cols = cells.iter().map(|p| p.coordinate.1);
+      assert!(
+        rows.clone().min() == Some(0)
+          || rows.max() == Some(ROWS - 1)
+          || cols.clone().min() == Some(0)
+          || cols.max() == Some(COLS - 1),
+        "{} is off the border",
+        ship.ship_type.name()
+      );
+    }
+  }
+
+  #[test]
+  fn test_game_set_formation() {
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Easy, 1);
+    game.set_formation(Formation::Cluster).unwrap();
+    let code = Board::with_formation(Formation::Cluster).unwrap().to_code();
+    assert_eq!(game.player().player_board().to_code(), code);
+
+    // play again keeps the formation, unharmed
+    let computer = game.computer().player_board().to_code();
+    while !game.is_won() {
+      game.bot_fire();
+    }
+    game.reset();
+    assert_eq!(game.player().player_board().to_code(), code);
+    assert!(game.player().player_board().ships_sunk().is_empty());
+    assert_ne!(game.computer().player_board().to_code(), computer);
+  }
+
+  #[test]
+  fn test_board_last_ship_region() {
+    let mut board = Board::from_code("I.90.2.4;O.90.7.7").unwrap();
+    assert_eq!(board.last_ship_region(), None);
+    board.scuttle("1".into()).unwrap();
+    let at = |row, col| Coord::new(row, col).unwrap();
+    // the upright I spans rows 2 to 4 of column 5, the region a cell more around it
+    assert_eq!(board.last_ship_region(), Some((at(1, 4), at(5, 6))));
+    assert!(board
+      .pos_by_ship("0".into())
+      .iter()
+      .all(|p| (2..=4).contains(&p.coordinate.0) && p.coordinate.1 == 5));
+
+    // always more cells than the ship has, even in a corner
+    let area = |(from, to): (Coord, Coord)| {
+      let ((top, left), (bottom, right)) = (from.into(), to.into());
+      (bottom - top + 1) * (right - left + 1)
+    };
+    assert!(area(board.last_ship_region().unwrap()) > ShipType::I.cell_count());
+    let mut board = Board::from_code("O.90.-1.-1;D.90.7.7").unwrap();
+    board.scuttle("1".into()).unwrap();
+    assert_eq!(board.last_ship_region(), Some((at(0, 0), at(1, 1))));
+    let mut board = Board::from_code("O.90.8.8;D.90.0.0").unwrap();
+    board.scuttle("1".into()).unwrap();
+    assert_eq!(board.last_ship_region(), Some((at(8, 8), at(9, 9))));
+  }
+
+  #[test]
+  fn test_game_scuttle() {
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    let cell = Coord::try_from(game.player().player_board().ship_cells()[0]).unwrap();
+    let size = game
+      .player()
+      .player_board()
+      .cell_info(cell)
+      .ship
+      .unwrap()
+      .ship_type
+      .cell_count();
+    assert!(game.scuttle(cell).is_err());
+
+    game.allow_scuttle = true;
+    game.scuttle(cell).unwrap();
+    assert!(!game.is_user_turn());
+    assert_eq!(
+      game.player().player_board().cell_info(cell).status,
+      Status::Kill
+    );
+    game.bot_fire();
+    assert!(!game.can_scuttle());
+    assert!(game.scuttle(cell).is_err());
+    // the bonus lasts for a single volley
+    assert_eq!(game.max_shots(), 1 + size);
+    let shots = (0..2).filter_map(|col| Coord::new(9, col)).collect();
+    game.fire(&shots, false);
+    game.bot_fire();
+    assert_eq!(game.max_shots(), 1);
+
+    // saves and replays sink the ship at the same point
+    let board = game.player().player_board().to_string();
+    let loaded = Game::from_save(&game.to_save()).unwrap();
+    assert_eq!(loaded.player().player_board().to_string(), board);
+    let loaded = Game::from_save_bytes(&game.to_save_bytes()).unwrap();
+    assert_eq!(loaded.player().player_board().to_string(), board);
+    let replayed = game.state_at(1);
+    assert_eq!(replayed.max_shots(), 1 + size);
+  }
+
+  #[test]
+  fn test_board_region_has_ship() {
+    // an upright I at column 5 covers rows 2 to 4
+    let board = Board::from_code("I.90.2.4").unwrap();
+    let at = |row, col| Coord::new(row, col).unwrap();
+    assert!(board.region_has_ship(at(3, 5)));
+    // only the top cell of the ship is inside the region
+    assert!(board.region_has_ship(at(1, 6)));
+    assert!(board.region_has_ship(at(5, 4)));
+    assert!(!board.region_has_ship(at(0, 5)));
+    assert!(!board.region_has_ship(at(3, 7)));
+    assert!(!board.region_has_ship(at(9, 9)));
+  }
+
+  #[test]
+  fn test_game_radar() {
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    let cell = game.computer().player_board().ship_cells()[0];
+    let center = Coord::try_from(cell).unwrap();
+    let msg = game.radar(center).unwrap();
+    assert!(msg.starts_with("Radar: contact in region "), "{}", msg);
+    assert!(!game.is_user_turn());
+    assert!(game.history().is_empty());
+    assert_eq!(game.player().radar_sweeps_left(), RADAR_SWEEPS - 1);
+
+    game.turn = 0;
+    game.radar(Coord::new(0, 0).unwrap()).unwrap();
+    game.turn = 0;
+    assert_eq!(
+      game.radar(Coord::new(0, 0).unwrap()),
+      Err("No radar sweeps left".into())
+    );
+    assert!(game.is_user_turn());
+    assert_eq!(Coord::new(3, 3).unwrap().to_string(), "D4");
+
+    // saves and replays keep the sweeps left and whose turn it is
+    let water = (0..ROWS * COLS)
+      .filter_map(|i| Coord::new(i / COLS, i % COLS))
+      .find(|c| game.computer().player_board().cell_info(*c).ship.is_none())
+      .unwrap();
+    game.fire(&std::iter::once(water).collect(), false);
+    game.radar(center).unwrap();
+    assert!(game.is_user_turn());
+    for loaded in [
+      Game::from_save(&game.to_save()).unwrap(),
+      Game::from_save_bytes(&game.to_save_bytes()).unwrap(),
+      game.state_at(game.history().len()),
+    ] {
+      assert!(loaded.is_user_turn());
+      assert_eq!(loaded.player().radar_sweeps_left(), 0);
+      assert_eq!(loaded.computer().radar_sweeps_left(), RADAR_SWEEPS - 1);
+      assert_eq!(loaded.radars, game.radars);
+    }
+  }
+
+  #[test]
+  fn test_game_armor() {
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    game.set_armor(2);
+    let cell = game.computer().player_board().ship_cells()[0];
+    let shot = [Coord::try_from(cell).unwrap()]
+      .iter()
+      .copied()
+      .collect::<BTreeSet<_>>();
+    let status = |game: &Game| {
+      (
+        game.computer().player_board().positions[cell.0][cell.1].status,
+        game.player().opponent_board().positions[cell.0][cell.1].status,
+      )
+    };
+
+    // the first hit only dents the armor, the cell can still be fired at
+    let msg = game.fire(&shot, false);
+    assert_eq!(msg, "You have 0 hit. You dented armor 1.");
+    assert_eq!(status(&game), (Status::Live, Status::Live));
+    assert_eq!(
+      game.computer().player_board().positions[cell.0][cell.1].hits,
+      1
+    );
+    assert!(game.player().opponent_board().fired_positions().is_empty());
+
+    game.bot_fire();
+    game.fire(&shot, false);
+    assert_eq!(status(&game), (Status::Hit, Status::Hit));
+
+    // and armor survives a save
+    let loaded = Game::from_save(&game.to_save()).unwrap();
+    assert_eq!(loaded.armor, 2);
+    assert_eq!(status(&loaded), (Status::Hit, Status::Hit));
+  }
+
+  #[test]
+  fn test_player_total_shots() {
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Easy, 1);
+    game.practice = true;
+    let row = |row: usize, cols: usize| (0..cols).filter_map(|col| Coord::new(row, col)).collect();
+    game.fire(&row(0, 2), false);
+    game.bot_fire();
+    game.fire(&row(1, 3), false);
+    assert_eq!(game.player().total_shots(), 5);
+    assert_eq!(game.computer().total_shots(), game.history()[1].shots.len());
+
+    // the same after loading, one volley less after an undo
+    let loaded = Game::from_save(&game.to_save()).unwrap();
+    assert_eq!(loaded.player().total_shots(), 5);
+    assert!(game.undo());
+    assert_eq!(game.player().total_shots(), 2);
+  }
+
+  #[test]
+  fn test_game_shot_analysis() {
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Easy, 1);
+    game.fire(
+      &(0..5).filter_map(|col| Coord::new(0, col)).collect(),
+      false,
+    );
+    let analysis = game.shot_analysis(0);
+    // nothing sunk yet, so none of the shots were needed
+    assert_eq!(analysis.min_shots, 0);
+    assert_eq!(analysis.actual_shots, 5);
+    assert_eq!(analysis.efficiency(), 0.0);
+
+    let analysis = ShotAnalysis {
+      actual_shots: 50,
+      min_shots: 20,
+    };
+    assert_eq!(analysis.efficiency(), 0.4);
+    assert_eq!(analysis.grade(), 'C');
+    assert_eq!(game.shot_analysis(1).efficiency(), 0.0);
+
+    // the losing side only needed the shots for the ships it did sink
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    game.set_armor(2);
+    while !game.is_won() {
+      game.bot_fire();
+    }
+    let loser = if game.player().player_board().ships_alive().is_empty() {
+      0
+    } else {
+      1
+    };
+    let winner_fleet = game.players[1 - loser].player_board();
+    let sunk = winner_fleet
+      .ships_sunk()
+      .iter()
+      .map(|s| s.ship_type.cell_count())
+      .sum::<usize>();
+    let analysis = game.shot_analysis(loser);
+    assert_eq!(analysis.min_shots, sunk * 2);
+    assert!(analysis.min_shots < winner_fleet.ship_cells().len() * 2);
+    assert!(analysis.efficiency() < 1.0);
+    assert_eq!(
+      game.shot_analysis(1 - loser).min_shots,
+      game.players[loser].player_board().ship_cells().len() * 2
+    );
+  }
+
+  #[test]
+  fn test_game_sinkings() {
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    game.practice = true;
+    let board = game.computer().player_board();
+    let ship = board.ships[1].clone();
+    let cells = board
+      .pos_by_ship(ship.id.clone())
+      .iter()
+      .filter_map(|p| Coord::try_from(p.coordinate).ok())
+      .collect::<BTreeSet<_>>();
+    // all but one cell first, the ship sinks with the second volley
+    let first = cells.iter().skip(1).copied().collect::<BTreeSet<_>>();
+    let rest = cells.iter().take(1).copied().collect::<BTreeSet<_>>();
+
+    game.fire(&first, false);
+    game.bot_fire();
+    assert!(game.sinkings().is_empty());
+    game.fire(&rest, false);
+    assert_eq!(
+      game.sinkings(),
+      &[Sinking {
+        volley: 2,
+        turn: 0,
+        ship_type: ship.ship_type.clone(),
+      }]
+    );
+    // replays rebuild them
+    assert_eq!(game.state_at(3).sinkings(), game.sinkings());
+    assert!(game.state_at(2).sinkings().is_empty());
+
+    game.undo();
+    assert!(game.sinkings().is_empty());
+  }
+
+  #[test]
+  fn test_game_hint() {
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    let cell = game.computer().player_board().ship_cells()[0];
+    let shots = [cell, (9, 9), (0, 0)]
+      .iter()
+      .filter_map(|c| Coord::try_from(*c).ok())
+      .collect::<BTreeSet<_>>();
+    game.fire(&shots, false);
+    game.bot_fire();
+
+    let hint = game.hint().unwrap();
+    let (row, col) = hint.into();
+    assert!(row < ROWS && col < COLS);
+    assert!(!shots.contains(&hint));
+    // next to the known hit, like the hard computer
+    let board = game.player().opponent_board();
+    assert_eq!(board.cell_info(hint).status, Status::Space);
+    assert!(row.abs_diff(cell.0) <= 2 && col.abs_diff(cell.1) <= 2);
+    assert_eq!(game.player().hints_left(), HINTS - 1);
+    assert!(game.is_user_turn());
+
+    // saves keep the hints used
+    for loaded in [
+      Game::from_save(&game.to_save()).unwrap(),
+      Game::from_save_bytes(&game.to_save_bytes()).unwrap(),
+    ] {
+      assert_eq!(loaded.player().hints_left(), HINTS - 1);
+      assert_eq!(loaded.computer().hints_left(), HINTS);
+    }
+
+    game.players[0].hints = 0;
+    assert_eq!(game.hint(), Err("No hints left".into()));
+  }
+
+  #[test]
+  fn test_hard_strategy_skips_sunk_surroundings() {
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Hard, 2);
+    game.set_no_touching(true);
+    // the fleets keep a free cell between ships
+    for player in game.players.iter() {
+      let board = player.player_board();
+      for ship in board.ships.iter() {
+        assert!(board
+          .pos_by_ship(ship.id.clone())
+          .iter()
+          .flat_map(|p| adjacent_coordinates(p.coordinate))
+          .all(|(x, y)| board.positions[x][y]
+            .ship_id
+            .as_ref()
+            .is_none_or(|id| *id == ship.id)));
+      }
+    }
+
+    // sink one of the player's ships for the computer
+    let board = game.player().player_board();
+    let ship = board.ships[0].id.clone();
+    let cells = board
+      .pos_by_ship(ship)
+      .iter()
+      .map(|p| p.coordinate)
+      .collect::<Vec<_>>();
+    game.turn = 1;
+    game.fire(&coords(&cells), true);
+    let surroundings = cells
+      .iter()
+      .flat_map(|c| adjacent_coordinates(*c))
+      .filter(|c| !cells.contains(c))
+      .collect::<BTreeSet<_>>();
+    assert!(!surroundings.is_empty());
+
+    while !game.is_won() {
+      if game.is_user_turn() {
+        game.turn = 1;
+      }
+      let shots = game.generate_bot_firing_coordinates();
+      assert!(shots.is_disjoint(&surroundings), "{:?}", shots);
+      game.fire(&coords(&shots), true);
+    }
+  }
+
+  #[test]
+  fn test_reverse_rows_of_cols() {
+    let ship = [
+      [Status::Live, Status::Live, Status::Space],
+      [Status::Space, Status::Live, Status::Space],
+      [Status::Space, Status::Space, Status::Live],
+    ];
+    let expected = [
+      [Status::Space, Status::Space, Status::Live],
+      [Status::Space, Status::Live, Status::Space],
+      [Status::Live, Status::Live, Status::Space],
+    ];
+    assert_eq!(reverse_rows_of_cols(ship), expected);
+  }
+
   #[test]
-  fn test_game_is_valid_rule() {
-    let mut game = Game::new(Rule::Default, Difficulty::Easy);
-    assert!(game.is_valid_rule(0));
-    assert!(!game.is_valid_rule(1));
+  fn test_reverse_cols_of_rows() {
+    let ship = [
+      [Status::Live, Status::Live, Status::Space],
+      [Status::Space, Status::Live, Status::Space],
+      [Status::Space, Status::Space, Status::Space],
+    ];
+    let expected = [
+      [Status::Space, Status::Live, Status::Live],
+      [Status::Space, Status::Live, Status::Space],
+      [Status::Space, Status::Space, Status::Space],
+    ];
+    assert_eq!(reverse_cols_of_rows(ship), expected);
+  }
 
-    game.rule = Rule::Fury;
+  #[test]
+  fn test_transpose() {
+    let ship = [
+      [Status::Live, Status::Live, Status::Space],
+      [Status::Space, Status::Live, Status::Space],
+      [Status::Space, Status::Space, Status::Space],
+    ];
+    let expected = [
+      [Status::Live, Status::Space, Status::Space],
+      [Status::Live, Status::Live, Status::Space],
+      [Status::Space, Status::Space, Status::Space],
+    ];
+    assert_eq!(transpose(ship), expected);
+  }
 
-    assert!(game.is_valid_rule(0));
-    assert!(game.is_valid_rule(3));
-    assert!(!game.is_valid_rule(4));
+  #[test]
+  fn test_ship_type_get_shape() {
+    let ship = ShipType::H;
+    assert_eq!(
+      ship.get_shape(90),
+      [
+        [Status::Live, Status::Space, Status::Live],
+        [Status::Live, Status::Live, Status::Live],
+        [Status::Live, Status::Space, Status::Live],
+      ]
+    );
+    assert_eq!(
+      ship.get_shape(180),
+      [
+        [Status::Live, Status::Live, Status::Live],
+        [Status::Space, Status::Live, Status::Space],
+        [Status::Live, Status::Live, Status::Live],
+      ]
+    );
+    let ship = ShipType::V;
+    assert_eq!(
+      ship.get_shape(270),
+      [
+        [Status::Space, Status::Live, Status::Space],
+        [Status::Live, Status::Space, Status::Live],
+        [Status::Live, Status::Space, Status::Live],
+      ]
+    );
+    assert_eq!(
+      ship.get_shape(360),
+      [
+        [Status::Live, Status::Live, Status::Space],
+        [Status::Space, Status::Space, Status::Live],
+        [Status::Live, Status::Live, Status::Space],
+      ]
+    );
+  }
 
-    game.rule = Rule::Charge;
+  #[test]
+  fn test_ship_is_overlapping() {
+    let ship = Ship::new(ShipType::H, &mut rand::thread_rng());
 
-    assert!(game.is_valid_rule(0));
-    assert!(!game.is_valid_rule(1));
+    assert!(!ship.is_overlapping(&[], (0, 0)));
+    assert!(!ship.is_overlapping(&[vec![]], (0, 0)));
+
+    let mut positions = (0..ROWS)
+      .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
+      .collect::<Vec<_>>();
+    // should pass as there is no overlap in default
+    assert!(!ship.is_overlapping(&positions, (0, 0)));
+
+    positions[1][5] = Position {
+      coordinate: (1, 5),
+      ship_id: Some("123".into()),
+      status: Status::Live,
+      hits: 0,
+    };
+    // should fail when there is overlap
+    assert!(ship.is_overlapping(&positions, (1, 5)));
   }
 
   #[test]
-  fn test_game_fire() {
-    let mut game = Game::new(Rule::Default, Difficulty::Easy);
+  fn test_ship_draw() {
+    let ship = Ship {
+      id: "123".into(),
+      rotation: 90,
+      alive: true,
+      ship_type: ShipType::H,
+    };
+    let mut positions = (0..ROWS)
+      .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
+      .collect::<Vec<_>>();
+    assert!(ship.draw(&mut positions, (5, 5)));
+    assert_grid_eq(
+      &positions,
+      &[
+        "..........",
+        "..........",
+        "..........",
+        "..........",
+        "..........",
+        ".....#.#..",
+        ".....###..",
+        ".....#.#..",
+        "..........",
+        "..........",
+      ],
+    );
+    assert!(ship.is_overlapping(&positions, (5, 5)));
+  }
 
-    let mut shots = BTreeSet::new();
-    shots.insert((1, 1));
-    shots.insert((3, 3));
+  #[test]
+  fn test_ship_diagonal_rotations() {
+    let empty = || {
+      (0..ROWS)
+        .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
+        .collect::<Vec<_>>()
+    };
+    let backslash = vec![(0, 0), (1, 1), (2, 2)];
+    let slash = vec![(0, 2), (1, 1), (2, 0)];
+    for (rotation, expected) in ROTATIONS
+      .iter()
+      .zip([&backslash, &slash, &backslash, &slash])
+    {
+      let ship = Ship {
+        id: "d".into(),
+        rotation: *rotation,
+        alive: true,
+        ship_type: ShipType::D,
+      };
+      assert_eq!(
+        ship.live_cells((0, 0)).as_ref(),
+        Some(expected),
+        "rotation {}",
+        rotation
+      );
+
+      // the bottom right corner is the last start the whole diagonal fits in
+      let (row, col) = (ROWS - 3, COLS - 3);
+      let mut positions = empty();
+      assert!(ship.draw(&mut positions, (row as isize, col as isize)));
+      let drawn = positions
+        .iter()
+        .flatten()
+        .filter(|p| p.status == Status::Live)
+        .map(|p| (p.coordinate.0 - row, p.coordinate.1 - col))
+        .collect::<Vec<_>>();
+      assert_eq!(&drawn, expected);
+      assert!(!ship.draw(&mut empty(), (row as isize + 1, col as isize)));
+      assert!(!ship.draw(&mut empty(), (row as isize, col as isize + 1)));
+    }
+    assert_eq!(ShipType::D.cell_count(), 3);
+  }
 
-    let msg = game.fire(&shots, false);
+  #[test]
+  fn test_ship_draw_near_edge() {
+    // upright the I leaves the right column of its box empty, lying down it spans the box
+    let mut ship = Ship {
+      id: "123".into(),
+      rotation: 90,
+      alive: true,
+      ship_type: ShipType::I,
+    };
+    let empty = || {
+      (0..ROWS)
+        .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
+        .collect::<Vec<_>>()
+    };
+    let start = (0, COLS as isize - 2);
+    assert!(ship.shape().iter().all(|row| row[2] == Status::Space));
 
-    assert!(!msg.is_empty());
-    assert!(!game.is_user_turn());
-    assert!(!game.winner.is_some());
+    let mut positions = empty();
+    assert!(ship.draw(&mut positions, start));
+    assert_eq!(
+      positions
+        .iter()
+        .flatten()
+        .filter(|p| p.status == Status::Live)
+        .count(),
+      ship.footprint()
+    );
+
+    // rotated the live cells would hang off the right edge
+    ship.rotation = 180;
+    assert!(ship.live_cells(start).is_none());
+    let mut positions = empty();
+    assert!(!ship.draw(&mut positions, start));
+    assert!(positions
+      .iter()
+      .flatten()
+      .all(|p| p.status == Status::Space));
+  }
+
+  #[test]
+  fn test_board_new() {
+    let opponent_board = Board::new(false, &mut rand::thread_rng());
+
+    // should be empty board initially
+    assert_grid_eq(&opponent_board.positions, &[".........."; ROWS]);
+
+    let my_board = Board::new(true, &mut rand::thread_rng());
+
+    // should be empty board initially
+    assert_eq!(my_board.ships.len(), 4);
+    assert_eq!(my_board.positions.len(), ROWS);
+    // check if all ships are placed on the board
+    my_board.ships.iter().for_each(|it| {
+      let found = my_board
+        .positions
+        .iter()
+        .flat_map(|pr| pr.iter())
+        .filter(|pc| pc.ship_id.is_some() && pc.ship_id.clone().unwrap() == it.id)
+        .collect::<Vec<_>>();
+      match it.ship_type {
+        ShipType::X => assert!(found.len() == 5, "ship X not placed!"),
+        ShipType::V => assert!(found.len() == 5, "ship V not placed!"),
+        ShipType::H => assert!(found.len() == 7, "ship H not placed!"),
+        ShipType::I => assert!(found.len() == 3, "ship I not placed!"),
+        ShipType::O | ShipType::D => panic!("ship {:?} isn't in the initial fleet", it.ship_type),
+      }
+    })
+  }
+
+  #[test]
+  fn test_board_mirror() {
+    let board = Board::from_code("I.90.2.4;V.360.5.0;D.90.0.6").unwrap();
+    let mirror = board.mirror(&mut StdRng::seed_from_u64(1)).unwrap();
+    let mut reflected = board
+      .ship_cells()
+      .into_iter()
+      .map(|(row, col)| (row, COLS - 1 - col))
+      .collect::<Vec<_>>();
+    reflected.sort_unstable();
+    assert_eq!(mirror.ship_cells(), reflected);
+    assert_eq!(mirror.validate(), Ok(()));
+    // ships get ids of their own
+    let ids = board
+      .ships
+      .iter()
+      .chain(mirror.ships.iter())
+      .map(|s| s.id.clone())
+      .collect::<BTreeSet<_>>();
+    assert_eq!(ids.len(), 6);
+
+    // the reflection of a line in the last column starts left of the board
+    let mirror = Board::from_code("I.90.0.8")
+      .unwrap()
+      .mirror(&mut StdRng::seed_from_u64(1))
+      .unwrap();
+    assert_eq!(mirror.to_code(), "I.90.0.-1");
+    assert_eq!(
+      Board::from_code(&mirror.to_code()).unwrap().ship_cells(),
+      mirror.ship_cells()
+    );
+
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 3);
+    game.set_mirrored(true);
+    let [player, computer] = [game.player(), game.computer()].map(|p| p.player_board());
+    let mirror = player.mirror(&mut StdRng::seed_from_u64(1)).unwrap();
+    assert_eq!(mirror.ship_cells(), computer.ship_cells());
+    let loaded = Game::from_save(&game.to_save()).unwrap();
+    assert!(loaded.mirrored);
+  }
+
+  #[test]
+  fn test_game_set_win_threshold() {
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    assert!(matches!(
+      game.set_win_threshold(Some(0)),
+      Err(GameError::Rule(_))
+    ));
+    assert!(matches!(
+      game.set_win_threshold(Some(5)),
+      Err(GameError::Rule(_))
+    ));
+    assert_eq!(game.win_threshold, None);
+    game.set_win_threshold(Some(4)).unwrap();
+    assert_eq!(game.win_threshold, Some(4));
+    game.set_win_threshold(None).unwrap();
+    assert_eq!(game.win_threshold, None);
+  }
+
+  #[test]
+  fn test_game_empty_fleet() {
+    let mut game = Game::with_seed(Rule::Barrage, Difficulty::Easy, 1);
+    assert!(matches!(
+      game.set_fleet(vec![]),
+      Err(GameError::Placement(_))
+    ));
+    // the old fleets stay and the game goes on
+    assert_eq!(game.computer().player_board().ships_alive().len(), 4);
+    let shot = BTreeSet::from([Coord::new(0, 0).unwrap()]);
+    game.fire(&shot, false);
+    assert!(!game.is_won());
+    assert!(Board::from_code("").is_err());
+
+    game.barrage_shots = 0;
+    assert_eq!(game.max_shots(), 1);
+  }
+
+  #[test]
+  fn test_game_uniform_fleet() {
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    game.set_fleet(ShipType::I.uniform_fleet()).unwrap();
+    for player in [game.player(), game.computer()] {
+      let board = player.player_board();
+      assert_eq!(board.ships.len(), 4);
+      assert!(board.ships.iter().all(|ship| ship.ship_type == ShipType::I));
+      // every ship has all of its cells, none shared
+      assert_eq!(board.ship_cells().len(), 4 * ShipType::I.cell_count());
+      assert_eq!(board.occupancy(), 0.12);
+      assert_eq!(board.validate(), Ok(()));
+    }
+
+    // and play again keeps it
+    game.reset();
+    assert!(game
+      .player()
+      .player_board()
+      .ships
+      .iter()
+      .all(|ship| ship.ship_type == ShipType::I));
+  }
+
+  #[test]
+  fn test_board_legal_placements() {
+    let empty = Board::new(false, &mut rand::thread_rng());
+    // a line covers 3 of 10 cells in every row or column, 8 ways each, upright or lying down
+    let lines = empty.legal_placements(&ShipType::I);
+    assert_eq!(lines.len(), 2 * 10 * 8);
+    // the box sticks out over the edge for a line along the left column
+    assert!(lines.contains(&((0, -1), 90)));
+    // a dot fits on every cell, whatever its rotation
+    assert_eq!(empty.legal_placements(&ShipType::O).len(), ROWS * COLS);
+
+    let mut board = Board::from_code("I.90.0.0").unwrap();
+    let touching = board.legal_placements(&ShipType::I);
+    assert!(touching.len() < lines.len());
+    board.no_touching = true;
+    let apart = board.legal_placements(&ShipType::I);
+    assert!(apart.len() < touching.len());
+    assert!(apart.iter().all(|(start, rotation)| {
+      let ship = Ship {
+        id: String::default(),
+        rotation: *rotation,
+        alive: true,
+        ship_type: ShipType::I,
+      };
+      ship
+        .live_cells(*start)
+        .unwrap()
+        .iter()
+        .all(|c| !board.is_near_ship(*c))
+    }));
+  }
+
+  #[test]
+  fn test_board_reset() {
+    let mut board = Board::new(true, &mut StdRng::seed_from_u64(1));
+    let shots = (0..ROWS).map(|row| (row, row)).collect();
+    board.take_fire(&shots, true);
+    assert!(board
+      .positions()
+      .iter()
+      .any(|p| p.status == Status::Hit || p.status == Status::Miss));
+    let grid = board.positions.as_ptr();
+
+    board.reset(true, &mut StdRng::seed_from_u64(2));
+    assert_eq!(board.positions.as_ptr(), grid);
+    assert_eq!(board.validate(), Ok(()));
+    assert_eq!(
+      board.ships_alive().len(),
+      ShipType::get_initial_ships().len()
+    );
+    assert!(board.firing_status().is_empty());
+    assert!(board
+      .positions()
+      .iter()
+      .all(|p| p.status == Status::Live || p.status == Status::Space));
+    // the same fleet a new board gets from the same seed
+    let fresh = Board::new(true, &mut StdRng::seed_from_u64(2));
+    assert_eq!(board.ship_cells(), fresh.ship_cells());
+
+    board.reset(false, &mut StdRng::seed_from_u64(3));
+    assert!(board.ship_cells().is_empty());
+  }
+
+  #[test]
+  fn test_board_clues_around() {
+    let mut board = Board::new(false, &mut StdRng::seed_from_u64(1));
+    board.positions[3][3].status = Status::Miss;
+    board.positions[3][4].status = Status::Hit;
+    board.positions[4][5].status = Status::Kill;
+    board.positions[5][5].status = Status::Live;
+    board.positions[4][4].status = Status::Miss;
+    let at = |row, col| Coord::new(row, col).unwrap();
+
+    let clues = board.clues_around(at(4, 4));
+    assert_eq!(
+      clues,
+      vec![
+        (at(3, 3), Clue::Miss),
+        (at(3, 4), Clue::Hit),
+        (at(3, 5), Clue::Open),
+        (at(4, 3), Clue::Open),
+        (at(4, 5), Clue::Wreck),
+        (at(5, 3), Clue::Open),
+        (at(5, 4), Clue::Open),
+        (at(5, 5), Clue::Hit),
+      ]
+    );
+    // corners only have three neighbours
+    assert_eq!(board.clues_around(at(0, 0)).len(), 3);
+  }
+
+  #[test]
+  fn test_board_occupancy() {
+    let board = Board::new(true, &mut rand::thread_rng());
+    let cells = ShipType::get_initial_ships()
+      .iter()
+      .map(|t| t.cell_count())
+      .sum::<usize>();
+    assert_eq!(cells, 5 + 5 + 7 + 3);
+    assert_eq!(board.occupancy(), 0.2);
+    assert_eq!(Board::new(false, &mut rand::thread_rng()).occupancy(), 0.0);
+  }
+
+  #[test]
+  fn test_ship_type_without_diagonals() {
+    let fleet = ShipType::without_diagonals(&ShipType::get_initial_ships());
+    assert_eq!(fleet, [ShipType::I, ShipType::I, ShipType::H, ShipType::I]);
+    assert!(ShipType::D.is_diagonal());
+    assert!(!ShipType::O.is_diagonal());
+
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    game.set_fleet(fleet).unwrap();
+    for player in [game.player(), game.computer()] {
+      let ships = &player.player_board().ships;
+      assert_eq!(ships.len(), ShipType::get_initial_ships().len());
+      assert!(ships.iter().all(|s| !s.ship_type.is_diagonal()));
+    }
+  }
+
+  #[test]
+  fn test_ship_type_cell_count() {
+    // the same sizes test_board_new finds on the board
+    assert_eq!(ShipType::X.cell_count(), 5);
+    assert_eq!(ShipType::V.cell_count(), 5);
+    assert_eq!(ShipType::H.cell_count(), 7);
+    assert_eq!(ShipType::I.cell_count(), 3);
+
+    let board = Board::new(true, &mut StdRng::seed_from_u64(1));
+    for ship in board.ships.iter() {
+      assert_eq!(
+        board.pos_by_ship(ship.id.clone()).len(),
+        ship.ship_type.cell_count(),
+        "{} doesn't match its board cells",
+        ship.ship_type.name()
+      );
+    }
+  }
+
+  #[test]
+  fn test_board_cell_info() {
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    let board = game.player().player_board();
+    let ship = board
+      .ships
+      .iter()
+      .find(|s| s.ship_type == ShipType::H)
+      .unwrap();
+    let cells = board
+      .pos_by_ship(ship.id.clone())
+      .iter()
+      .filter_map(|p| Coord::new(p.coordinate.0, p.coordinate.1))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      board.cell_info(cells[0]),
+      CellInfo {
+        status: Status::Live,
+        ship: Some(ShipHealth {
+          ship_type: ShipType::H,
+          cells_afloat: 7,
+          alive: true,
+        })
+      }
+    );
+
+    // the computer hits the ship
+    game.fire(&std::iter::once(cells[0]).collect(), false);
+    game.fire(&std::iter::once(cells[0]).collect(), true);
+    let info = game.player().player_board().cell_info(cells[0]);
+    assert_eq!(info.status, Status::Hit);
+    assert_eq!(info.ship.map(|s| s.cells_afloat), Some(6));
+
+    // the tracking board only knows what was fired at
+    let computer_ship = game.computer().player_board().ships[0].clone();
+    let cell = game
+      .computer()
+      .player_board()
+      .pos_by_ship(computer_ship.id.clone())
+      .iter()
+      .find_map(|p| Coord::new(p.coordinate.0, p.coordinate.1))
+      .unwrap();
+    let info = game.player().opponent_board().cell_info(cell);
+    assert!(info.ship.is_none());
+
+    // sinking it marks every cell
+    let cells = game
+      .computer()
+      .player_board()
+      .pos_by_ship(computer_ship.id)
+      .iter()
+      .filter_map(|p| Coord::new(p.coordinate.0, p.coordinate.1))
+      .collect::<BTreeSet<_>>();
+    game.turn = 0;
+    game.fire(&cells, false);
+    let info = game.computer().player_board().cell_info(cell);
+    assert_eq!(info.status, Status::Kill);
+    let ship = info.ship.unwrap();
+    assert_eq!(ship.ship_type.name(), computer_ship.ship_type.name());
+    assert!(!ship.alive);
+    assert_eq!(ship.cells_afloat, 0);
+  }
+
+  #[test]
+  fn test_board_validate() {
+    let mut board = Board::new(true, &mut rand::thread_rng());
+    assert_eq!(board.validate(), Ok(()));
+
+    // orphan ship id
+    let pos = board
+      .positions
+      .iter_mut()
+      .flat_map(|pr| pr.iter_mut())
+      .find(|pc| pc.ship_id.is_some())
+      .unwrap();
+    pos.ship_id = Some("orphan".into());
+    assert!(board.validate().is_err());
+
+    let mut board = Board::new(true, &mut rand::thread_rng());
+    board.ships[0].id = "moved".into();
+    assert!(board.validate().is_err());
   }
 
   #[test]
-  fn test_game_generate_firing_coordinates() {
-    let game = Game::new(Rule::Default, Difficulty::Easy);
+  fn test_board_code() {
+    let board = Board::new(true, &mut rand::thread_rng());
+    let code = board.to_code();
 
-    let shots = game.generate_bot_firing_coordinates();
-    assert_eq!(shots.len(), 1);
+    // four short fields per ship, no ids
+    assert!(code.len() <= board.ships.len() * "X.360.9.9;".len());
+    let decoded = Board::from_code(&code).unwrap();
+    assert_eq!(decoded.as_grid(), board.as_grid());
+    decoded
+      .ships
+      .iter()
+      .zip(board.ships.iter())
+      .for_each(|(d, b)| {
+        assert!(d.ship_type == b.ship_type);
+        assert_eq!(d.rotation, b.rotation);
+        assert_eq!(
+          d.footprint(),
+          decoded.pos_by_ship(d.id.clone()).len(),
+          "ship footprint mismatch"
+        );
+      });
+    assert_eq!(decoded.to_code(), code);
 
-    let game = Game::new(Rule::Charge, Difficulty::Easy);
+    assert!(Board::from_code("").is_err());
+    assert!(Board::from_code("Z.90.0.0").is_err());
+    assert!(Board::from_code("X.45.0.0").is_err());
+    assert!(Board::from_code("X.90.8.0").is_err());
+    assert!(Board::from_code("H.90.0.0;H.90.0.0").is_err());
+  }
 
-    let shots = game.generate_bot_firing_coordinates();
-    assert_eq!(shots.len(), 1);
+  #[test]
+  fn test_board_load_from_path() {
+    let layout = Board::new(true, &mut StdRng::seed_from_u64(5));
+    let path = std::env::temp_dir().join(format!("battleship-{}.board", std::process::id()));
+    fs::write(&path, format!("{}\n", layout.to_code())).unwrap();
 
-    let game = Game::new(Rule::Fury, Difficulty::Easy);
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    game.set_computer_board(Board::load_from_path(&path).unwrap());
+    fs::remove_file(&path).unwrap();
+    assert_eq!(game.computer().player_board().as_grid(), layout.as_grid());
+    assert_eq!(game.computer().player_board().to_code(), layout.to_code());
+    // your own fleet stays random and nobody has fired yet
+    assert_ne!(game.player().player_board().to_code(), layout.to_code());
+    for player in [game.player(), game.computer()] {
+      assert!(player
+        .opponent_board()
+        .positions()
+        .iter()
+        .all(|p| p.status == Status::Space));
+    }
 
-    let shots = game.generate_bot_firing_coordinates();
-    assert_eq!(shots.len(), 4);
-  }
+    // a new game keeps the loaded fleet, unharmed
+    let player_board = game.player().player_board().to_code();
+    while !game.is_won() {
+      game.bot_fire();
+    }
+    game.reset();
+    assert_eq!(game.computer().player_board().to_code(), layout.to_code());
+    assert!(game.computer().player_board().ships_sunk().is_empty());
+    assert_ne!(game.player().player_board().to_code(), player_board);
 
-  #[test]
-  fn test_get_random_coordinate() {
-    let mut rng = rand::thread_rng();
-    assert!(get_random_coordinate(&mut rng, SHIP_SIZE) < (ROWS, COLS));
+    assert!(matches!(
+      Board::load_from_path(&path),
+      Err(GameError::Io(_))
+    ));
   }
 
   #[test]
-  fn test_reverse_rows_of_cols() {
-    let ship = [
-      [Status::Live, Status::Live, Status::Space],
-      [Status::Space, Status::Live, Status::Space],
-      [Status::Space, Status::Space, Status::Live],
-    ];
-    let expected = [
-      [Status::Space, Status::Space, Status::Live],
-      [Status::Space, Status::Live, Status::Space],
-      [Status::Live, Status::Live, Status::Space],
+  fn test_board_with_fleet_backtracking() {
+    // 8 ships covering most of the board, greedy placement would often get stuck
+    let fleet = [
+      ShipType::H,
+      ShipType::H,
+      ShipType::X,
+      ShipType::X,
+      ShipType::V,
+      ShipType::V,
+      ShipType::I,
+      ShipType::I,
     ];
-    assert_eq!(reverse_rows_of_cols(ship), expected);
-  }
+    let board = Board::with_fleet(&fleet, false, &mut StdRng::seed_from_u64(0)).unwrap();
+    assert_eq!(board.ships.len(), fleet.len());
+    assert_eq!(board.validate(), Ok(()));
 
-  #[test]
-  fn test_reverse_cols_of_rows() {
-    let ship = [
-      [Status::Live, Status::Live, Status::Space],
-      [Status::Space, Status::Live, Status::Space],
-      [Status::Space, Status::Space, Status::Space],
-    ];
-    let expected = [
-      [Status::Space, Status::Live, Status::Live],
-      [Status::Space, Status::Live, Status::Space],
-      [Status::Space, Status::Space, Status::Space],
-    ];
-    assert_eq!(reverse_cols_of_rows(ship), expected);
-  }
+    let again = Board::with_fleet(&fleet, false, &mut StdRng::seed_from_u64(0)).unwrap();
+    assert_eq!(again.as_grid(), board.as_grid());
 
-  #[test]
-  fn test_transpose() {
-    let ship = [
-      [Status::Live, Status::Live, Status::Space],
-      [Status::Space, Status::Live, Status::Space],
-      [Status::Space, Status::Space, Status::Space],
-    ];
-    let expected = [
-      [Status::Live, Status::Space, Status::Space],
-      [Status::Live, Status::Live, Status::Space],
-      [Status::Space, Status::Space, Status::Space],
-    ];
-    assert_eq!(transpose(ship), expected);
+    // more live cells than the board holds
+    let fleet = vec![ShipType::X; ROWS * COLS / 5 + 1];
+    assert!(Board::with_fleet(&fleet[..], false, &mut StdRng::seed_from_u64(3)).is_err());
   }
 
   #[test]
-  fn test_ship_type_get_shape() {
-    let ship = ShipType::H;
-    assert_eq!(
-      ship.get_shape(90),
-      [
-        [Status::Live, Status::Space, Status::Live],
-        [Status::Live, Status::Live, Status::Live],
-        [Status::Live, Status::Space, Status::Live],
-      ]
-    );
-    assert_eq!(
-      ship.get_shape(180),
-      [
-        [Status::Live, Status::Live, Status::Live],
-        [Status::Space, Status::Live, Status::Space],
-        [Status::Live, Status::Live, Status::Live],
-      ]
-    );
-    let ship = ShipType::V;
-    assert_eq!(
-      ship.get_shape(270),
-      [
-        [Status::Space, Status::Live, Status::Space],
-        [Status::Live, Status::Space, Status::Live],
-        [Status::Live, Status::Space, Status::Live],
-      ]
-    );
-    assert_eq!(
-      ship.get_shape(360),
-      [
-        [Status::Live, Status::Live, Status::Space],
-        [Status::Space, Status::Space, Status::Live],
-        [Status::Live, Status::Live, Status::Space],
-      ]
-    );
-  }
+  fn test_board_take_fire_sunk_ship_again() {
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Easy, 1);
+    let board = game.computer().player_board();
+    let id = board.ships[0].id.clone();
+    let cells = board
+      .pos_by_ship(id)
+      .iter()
+      .map(|p| p.coordinate)
+      .collect::<Vec<_>>();
+    game.fire(&coords(&cells), false);
+    let score = game.score(0);
+    assert_eq!(game.ships_sunk(), [0, 1]);
 
-  #[test]
-  fn test_ship_is_overlapping() {
-    let ship = Ship::new(ShipType::H);
+    let board = game.computer_mut().player_board_mut();
+    let (res, lost) = board.take_fire(&cells.iter().copied().collect(), true);
+    assert!(!lost);
+    assert_eq!(res.len(), cells.len());
+    assert!(res.values().all(|r| r.status != Status::Kill));
+    assert_eq!(board.ships_sunk().len(), 1);
 
-    assert!(!ship.is_overlapping(&[], (0, 0)));
-    assert!(!ship.is_overlapping(&[vec![]], (0, 0)));
+    // nor does it score again
+    game.turn = 0;
+    game.fire(&coords(&cells), false);
+    assert_eq!(game.ships_sunk(), [0, 1]);
+    assert_eq!(game.score(0), score);
+  }
 
-    let mut positions = (0..ROWS)
-      .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
+  #[test]
+  fn test_board_firing_status() {
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Easy, 1);
+    let board = game.computer().player_board();
+    let ship = board.ships[0].id.clone();
+    let cells = board
+      .pos_by_ship(board.ships[0].id.clone())
+      .iter()
+      .map(|p| p.coordinate)
       .collect::<Vec<_>>();
-    // should pass as there is no overlap in default
-    assert!(!ship.is_overlapping(&positions, (0, 0)));
+    assert!(game.player().opponent_board().firing_status().is_empty());
 
-    positions[1][5] = Position {
-      coordinate: (1, 5),
-      ship_id: Some("123".into()),
-      status: Status::Live,
-    };
-    // should fail when there is overlap
-    assert!(ship.is_overlapping(&positions, (1, 5)));
+    game.fire(&coords(&cells[..1]), false);
+    for board in &[
+      game.computer().player_board(),
+      game.player().opponent_board(),
+    ] {
+      assert_eq!(board.firing_status().len(), 1);
+      assert_eq!(board.firing_status()[&ship], "damaged");
+    }
+
+    game.turn = 0;
+    game.fire(&coords(&cells[1..]), false);
+    for board in &[
+      game.computer().player_board(),
+      game.player().opponent_board(),
+    ] {
+      assert_eq!(board.firing_status()[&ship], "sunk");
+    }
   }
 
   #[test]
-  fn test_ship_draw() {
-    let ship = Ship {
-      id: "123".into(),
-      rotation: 90,
-      alive: true,
-      ship_type: ShipType::H,
-    };
-    let mut positions = (0..ROWS)
-      .map(|r| (0..COLS).map(|c| Position::new((r, c))).collect::<Vec<_>>())
-      .collect::<Vec<_>>();
-    assert!(ship.draw(&mut positions, (5, 5)));
-    let p = positions
+  fn test_board_firing_status_uniform_fleet() {
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Easy, 1);
+    game.set_fleet(ShipType::I.uniform_fleet()).unwrap();
+    let board = game.computer().player_board();
+    let ids = board.ships.iter().map(|s| s.id.clone()).collect::<Vec<_>>();
+    let first = coords(
+      &board.pos_by_ship(ids[0].clone())[..1]
+        .iter()
+        .map(|p| p.coordinate)
+        .collect::<Vec<_>>(),
+    );
+    let second = board
+      .pos_by_ship(ids[1].clone())
       .iter()
-      .map(|row| {
-        row
-          .iter()
-          .map(|c| c.to_string())
-          .collect::<Vec<_>>()
-          .join("")
-      })
-      .collect::<Vec<_>>()
-      .join("\n");
-    assert_eq!(p, "          \n          \n          \n          \n          \n     🚀 🚀  \n     🚀🚀🚀  \n     🚀 🚀  \n          \n          ");
-    assert!(ship.is_overlapping(&positions, (5, 5)));
+      .map(|p| p.coordinate)
+      .collect::<Vec<_>>();
+    assert_eq!(board.ship_label(&ids[1]), Some("Line #2".into()));
+    assert_eq!(board.ship_label("z"), None);
+
+    // ships of the same type keep their own entries
+    game.fire(&first, false);
+    game.turn = 0;
+    game.fire(&coords(&second), false);
+    let tracking = game.player().opponent_board();
+    assert_eq!(tracking.firing_status().len(), 2);
+    assert_eq!(tracking.firing_status()[&ids[0]], "damaged");
+    assert_eq!(tracking.firing_status()[&ids[1]], "sunk");
+
+    let fleet = Board::from_code("I.90.0.0;O.90.5.5").unwrap();
+    assert_eq!(fleet.ship_label("0"), Some("Line".into()));
   }
 
   #[test]
-  fn test_board_new() {
-    let opponent_board = Board::new(false);
+  fn test_board_take_fire_single_cell_ship() {
+    let mut board = Board::with_fleet(
+      &[ShipType::O, ShipType::O],
+      false,
+      &mut StdRng::seed_from_u64(1),
+    )
+    .unwrap();
+    let cells = board.ship_cells();
+    assert_eq!(cells.len(), 2);
 
-    // should be empty board initially
-    assert_eq!(opponent_board.to_string(), "          \n          \n          \n          \n          \n          \n          \n          \n          \n          ");
+    // the first hit sinks it, only the last one loses the game
+    let (res, lost) = board.take_fire(&std::iter::once(cells[0]).collect(), true);
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[&cells[0]].status, Status::Kill);
+    assert!(!lost);
+    assert_eq!(board.ships_sunk().len(), 1);
 
-    let my_board = Board::new(true);
+    // firing at the wreck again is a miss and doesn't panic
+    let (res, lost) = board.take_fire(&std::iter::once(cells[0]).collect(), true);
+    assert_eq!(res[&cells[0]].status, Status::Miss);
+    assert!(!lost);
 
-    // should be empty board initially
-    assert_eq!(my_board.ships.len(), 4);
-    assert_eq!(my_board.positions.len(), ROWS);
-    // check if all ships are placed on the board
-    my_board.ships.iter().for_each(|it| {
-      let found = my_board
-        .positions
-        .iter()
-        .flat_map(|pr| pr.iter())
-        .filter(|pc| pc.ship_id.is_some() && pc.ship_id.clone().unwrap() == it.id)
-        .collect::<Vec<_>>();
-      match it.ship_type {
-        ShipType::X => assert!(found.len() == 5, "ship X not placed!"),
-        ShipType::V => assert!(found.len() == 5, "ship V not placed!"),
-        ShipType::H => assert!(found.len() == 7, "ship H not placed!"),
-        ShipType::I => assert!(found.len() == 3, "ship I not placed!"),
-      }
-    })
+    let (res, lost) = board.take_fire(&std::iter::once(cells[1]).collect(), false);
+    assert_eq!(res[&cells[1]].status, Status::Kill);
+    assert!(lost);
+    assert_eq!(board.validate(), Ok(()));
   }
 
   #[test]
   fn test_board_take_fire() {
-    let mut board = Board::new(true);
+    let mut board = Board::new(true, &mut rand::thread_rng());
 
     board.positions[1][1].status = Status::Space;
     board.positions[3][3].status = Status::Live;
@@ -854,12 +5163,12 @@ mod tests {
     shots.insert((1, 1));
     shots.insert((3, 3));
 
-    let (res, lost) = board.take_fire(&shots);
-    assert_eq!(res.get(&(1, 1)).unwrap(), &Status::Miss);
-    assert_eq!(res.get(&(3, 3)).unwrap(), &Status::Hit);
+    let (res, lost) = board.take_fire(&shots, true);
+    assert_eq!(res.get(&(1, 1)).unwrap().status, Status::Miss);
+    assert_eq!(res.get(&(3, 3)).unwrap().status, Status::Hit);
     assert!(!lost);
 
-    let mut board = Board::new(true);
+    let mut board = Board::new(true, &mut rand::thread_rng());
 
     // set a ship as hit except for one position
     let ship_id = board.ships[0].id.clone();
@@ -876,30 +5185,369 @@ mod tests {
     let mut shots = BTreeSet::new();
     shots.insert(c[0]);
 
-    let (res, lost) = board.take_fire(&shots);
-    assert_eq!(res.get(&c[0]).unwrap(), &Status::Kill);
+    let (res, lost) = board.take_fire(&shots, true);
+    assert_eq!(res.get(&c[0]).unwrap().status, Status::Kill);
     assert!(!lost);
   }
 
+  quickcheck::quickcheck! {
+    /// any volleys of in-bounds shots, repeats included, keep the fleet and the response consistent
+    fn prop_board_take_fire(seed: u64, armor: u8, reveal_full_ship_on_kill: bool, volleys: Vec<Vec<(u8, u8)>>) -> bool {
+      let mut board = Board::new(true, &mut StdRng::seed_from_u64(seed));
+      board.armor = 1 + usize::from(armor % 3);
+      volleys.iter().all(|volley| {
+        let shots = volley
+          .iter()
+          .map(|(row, col)| (usize::from(*row) % ROWS, usize::from(*col) % COLS))
+          .collect();
+        let (response, lost) = board.take_fire(&shots, reveal_full_ship_on_kill);
+        let kills_are_dead = response
+          .values()
+          .filter(|r| r.status == Status::Kill)
+          .all(|r| {
+            r.ship
+              .as_ref()
+              .and_then(|(id, _)| board.find_ship(id.clone()))
+              .is_some_and(|ship| !ship.alive)
+          });
+        lost == board.ships_alive().is_empty() && kills_are_dead
+      })
+    }
+  }
+
   #[test]
   fn test_board_update_status() {
-    let mut board = Board::new(false);
+    let mut board = Board::new(false, &mut rand::thread_rng());
 
+    let result = |status| ShotResult { status, ship: None };
     let mut res = BTreeMap::new();
-    res.insert((1, 1), Status::Miss);
-    res.insert((3, 3), Status::Hit);
-    res.insert((0, 2), Status::Kill);
+    res.insert((1, 1), result(Status::Miss));
+    res.insert((3, 3), result(Status::Hit));
+    res.insert((0, 2), result(Status::Kill));
 
-    let message = board.update_status(res, false);
+    let message = board.update_status(res, false, None, false);
     assert_eq!(message, "You have sunk a ship. You missed 1.");
 
     let mut res = BTreeMap::new();
-    res.insert((3, 3), Status::Hit);
-    res.insert((0, 2), Status::Hit);
+    res.insert((3, 3), result(Status::Hit));
+    res.insert((0, 2), result(Status::Hit));
 
-    let message = board.update_status(res.clone(), false);
+    let message = board.update_status(res.clone(), false, None, false);
     assert_eq!(message, "You have 2 hit.");
-    let message = board.update_status(res, true);
+    let message = board.update_status(res, true, None, false);
     assert_eq!(message, "Computer have 2 hit.");
   }
+
+  #[test]
+  fn test_board_update_status_verbose() {
+    let mut board = Board::new(true, &mut StdRng::seed_from_u64(1));
+    let ship = board
+      .ships
+      .iter()
+      .find(|s| s.ship_type == ShipType::X)
+      .unwrap()
+      .clone();
+    let cells = board
+      .pos_by_ship(ship.id.clone())
+      .iter()
+      .map(|p| p.coordinate)
+      .collect::<Vec<_>>();
+    let misses = board
+      .positions()
+      .iter()
+      .filter(|p| p.ship_id.is_none())
+      .map(|p| p.coordinate)
+      .take(3)
+      .collect::<Vec<_>>();
+    let shots = cells
+      .iter()
+      .take(2)
+      .chain(misses.iter())
+      .copied()
+      .collect::<BTreeSet<_>>();
+
+    let (res, _) = board.take_fire(&shots, true);
+    let mut tracking = Board::new(false, &mut StdRng::seed_from_u64(1));
+    let message = tracking.update_status(res.clone(), false, None, true);
+    assert_eq!(message, "You: hit X x2, missed x3.");
+
+    // the concise message stays the default
+    let message = tracking.update_status(res, false, None, false);
+    assert_eq!(message, "You have 2 hit. You missed 3.");
+
+    // sinking reports the ship once, not every revealed cell
+    let shots = cells.iter().skip(2).copied().collect::<BTreeSet<_>>();
+    let (res, _) = board.take_fire(&shots, true);
+    let message = tracking.update_status(res, true, Some("Bob"), true);
+    assert_eq!(message, "Bob: sunk X.");
+  }
+
+  #[test]
+  fn test_game_with_seeds() {
+    let play = |strategy_seed| {
+      let mut game = Game::with_seeds(Rule::Fury, Difficulty::Hard, 7, strategy_seed);
+      for _ in 0..4 {
+        game.bot_fire();
+      }
+      game
+    };
+    let a = play(1);
+    let b = play(2);
+    assert_eq!(
+      a.player().player_board().ship_cells(),
+      b.player().player_board().ship_cells()
+    );
+    assert_eq!(
+      a.computer().player_board().ship_cells(),
+      b.computer().player_board().ship_cells()
+    );
+    assert_ne!(a.history(), b.history());
+    // ship ids come from the seed as well
+    let ids = |game: &Game| {
+      game.players[1].boards[0]
+        .ships
+        .iter()
+        .map(|s| s.id.clone())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(&a), ids(&b));
+
+    // and the other way around
+    let c = Game::with_seeds(Rule::Fury, Difficulty::Hard, 8, 1);
+    assert_ne!(
+      a.computer().player_board().ship_cells(),
+      c.computer().player_board().ship_cells()
+    );
+  }
+
+  #[test]
+  fn test_game_score() {
+    let sink = |ship_type: ShipType| {
+      let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+      let board = game.computer().player_board();
+      let ship = board
+        .ships
+        .iter()
+        .find(|s| s.ship_type == ship_type)
+        .unwrap();
+      let shots = board
+        .pos_by_ship(ship.id.clone())
+        .iter()
+        .filter_map(|p| Coord::new(p.coordinate.0, p.coordinate.1))
+        .collect();
+      game.fire(&shots, false);
+      game.score(0)
+    };
+    assert_eq!(sink(ShipType::H), 70);
+    assert_eq!(sink(ShipType::I), 30);
+    assert!(sink(ShipType::H) > sink(ShipType::I));
+
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    game.practice = true;
+    let far = (0..ROWS)
+      .flat_map(|r| (0..COLS).filter_map(move |c| Coord::new(r, c)))
+      .find(|c| !game.computer().player_board().is_near_ship((*c).into()))
+      .unwrap();
+    game.fire(&std::iter::once(far).collect(), false);
+    assert_eq!(game.score(0), -1);
+    assert_eq!(game.score(1), 0);
+    assert!(game.undo());
+    assert_eq!(game.score(0), 0);
+  }
+
+  #[test]
+  fn test_board_next_unfired_from() {
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Easy, 1);
+    assert_eq!(
+      game.player().opponent_board().next_unfired_from((0, 0)),
+      Some((0, 1))
+    );
+    game.fire(&coords(&[(0, 1), (0, 2), (1, 0)]), false);
+    let board = game.player().opponent_board();
+    assert_eq!(board.next_unfired_from((0, 0)), Some((0, 3)));
+    assert_eq!(board.next_unfired_from((0, COLS - 1)), Some((1, 1)));
+    // wraps around from the last cell
+    assert_eq!(board.next_unfired_from((ROWS - 1, COLS - 1)), Some((0, 0)));
+
+    let all = (0..ROWS)
+      .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+      .collect::<Vec<_>>();
+    game.turn = 0;
+    game.fire(&coords(&all), false);
+    assert_eq!(
+      game.player().opponent_board().next_unfired_from((0, 0)),
+      None
+    );
+  }
+
+  #[test]
+  fn test_game_reveal_count() {
+    let tracked = |game: &Game, status: Status| {
+      game
+        .player()
+        .opponent_board()
+        .positions()
+        .into_iter()
+        .filter(|p| p.status == status)
+        .map(|p| p.coordinate)
+        .collect::<Vec<_>>()
+    };
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    game.set_reveal_count(3);
+    let revealed = tracked(&game, Status::Hit);
+    assert_eq!(revealed.len(), 3);
+    assert!(tracked(&game, Status::Kill).is_empty());
+    let fleet = game.computer().player_board().ship_cells();
+    assert!(revealed.iter().all(|c| fleet.contains(c)));
+    assert_eq!(game.ships_sunk(), [0, 0]);
+
+    // saves keep the same cells
+    let loaded = Game::from_save(&game.to_save()).unwrap();
+    assert_eq!(tracked(&loaded, Status::Hit), revealed);
+    let loaded = Game::from_save_bytes(&game.to_save_bytes()).unwrap();
+    assert_eq!(tracked(&loaded, Status::Hit), revealed);
+
+    // a new game reveals as many again, never more than the fleet has
+    game.reset();
+    assert_eq!(tracked(&game, Status::Hit).len(), 3);
+    game.set_reveal_count(100);
+    assert_eq!(tracked(&game, Status::Hit).len(), fleet.len());
+    assert_eq!(game.ships_sunk(), [0, 0]);
+  }
+
+  #[test]
+  fn test_game_save() {
+    let mut game = Game::with_seed(Rule::Barrage, Difficulty::Easy, 1);
+    game.barrage_shots = 4;
+    game.win_threshold = Some(2);
+    for _ in 0..6 {
+      game.bot_fire();
+    }
+
+    let loaded = Game::from_save(&game.to_save()).unwrap();
+    assert_eq!(loaded.history(), game.history());
+    assert_eq!(loaded.barrage_shots, 4);
+    assert_eq!(loaded.win_threshold, Some(2));
+    assert_eq!(loaded.is_user_turn(), game.is_user_turn());
+    assert_eq!(loaded.ships_sunk(), game.ships_sunk());
+    for (a, b) in loaded.players.iter().zip(game.players.iter()) {
+      assert_eq!(a.player_board().to_string(), b.player_board().to_string());
+      assert_eq!(
+        a.opponent_board().to_string(),
+        b.opponent_board().to_string()
+      );
+    }
+
+    let path = std::env::temp_dir().join(format!("battleship-{}.save", std::process::id()));
+    game.save_to_path(&path).unwrap();
+    assert_eq!(
+      Game::load_from_path(&path).unwrap().history(),
+      game.history()
+    );
+
+    // saves from another version are rejected instead of loading a broken game
+    let old = SaveData {
+      version: 0,
+      ..SaveData::new(&game)
+    };
+    fs::write(&path, serde_json::to_string(&old).unwrap()).unwrap();
+    assert!(matches!(
+      Game::load_from_path(&path),
+      Err(GameError::SaveLoad(msg)) if msg == unsupported_version(0)
+    ));
+    fs::remove_file(&path).unwrap();
+
+    assert!(Game::from_save("rule=Fury").is_err());
+  }
+
+  #[test]
+  fn test_game_save_binary() {
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Adaptive, 4);
+    game.set_no_touching(true);
+    game.reveal_full_ship_on_kill = false;
+    for _ in 0..9 {
+      game.bot_fire();
+    }
+
+    let bytes = game.to_save_bytes();
+    assert!(bytes.len() < game.to_save().len());
+    let loaded = Game::from_save_bytes(&bytes).unwrap();
+    assert_eq!(loaded.to_save(), game.to_save());
+    assert_eq!(loaded.is_user_turn(), game.is_user_turn());
+    for (a, b) in loaded.players.iter().zip(game.players.iter()) {
+      assert_eq!(a.player_board().to_string(), b.player_board().to_string());
+      assert_eq!(
+        a.opponent_board().to_string(),
+        b.opponent_board().to_string()
+      );
+    }
+
+    // the extension picks the format
+    let path = std::env::temp_dir().join(format!("battleship-{}.bin", std::process::id()));
+    game.save_to_path(&path).unwrap();
+    assert_eq!(fs::read(&path).unwrap(), bytes);
+    assert_eq!(
+      Game::load_from_path(&path).unwrap().to_save(),
+      game.to_save()
+    );
+    fs::remove_file(&path).unwrap();
+
+    let old = SaveData {
+      version: 0,
+      ..SaveData::new(&game)
+    };
+    assert!(matches!(
+      Game::from_save_bytes(&binary_options().serialize(&old).unwrap()),
+      Err(GameError::SaveLoad(msg)) if msg == unsupported_version(0)
+    ));
+    assert!(Game::from_save_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(Game::from_save_bytes(game.to_save().as_bytes()).is_err());
+  }
+
+  #[test]
+  fn test_game_reset() {
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Easy, 1);
+    game.player_mut().set_name(Some("Alice".into()));
+    game.practice = true;
+    while !game.is_won() {
+      game.bot_fire();
+    }
+
+    let board = game.computer().player_board().clone();
+    game.reset();
+    assert!(!game.is_won());
+    assert!(game.is_user_turn());
+    assert!(game.history().is_empty());
+    assert!(!game.undo());
+    assert_eq!(game.ships_sunk(), [0, 0]);
+    assert!(game.computer().player_board() != &board);
+    assert_eq!(game.player().name(), "Alice");
+    assert!(game.practice);
+  }
+
+  #[test]
+  fn test_game_fire_named_player() {
+    let mut game = Game::new(Rule::Default, Difficulty::Easy);
+    game.player_mut().set_name(Some("Alice".into()));
+    assert_eq!(game.player().name(), "Alice");
+    assert_eq!(game.computer().name(), "Computer");
+
+    let mut shots = BTreeSet::new();
+    shots.insert((1, 1));
+
+    let msg = game.fire(&coords(&shots), false);
+    assert!(msg.starts_with("Alice has "));
+    assert!(!msg.contains("You"));
+
+    // the end of the game names the players too
+    let cells = game.computer().player_board().ship_cells();
+    game.turn = 0;
+    assert_eq!(game.fire(&coords(&cells), false), "Alice won 🙌");
+
+    let mut game = Game::new(Rule::Default, Difficulty::Easy);
+    game.player_mut().set_name(Some("Alice".into()));
+    let cells = game.player().player_board().ship_cells();
+    game.turn = 1;
+    assert_eq!(game.fire(&coords(&cells), true), "Alice lost 🙁");
+  }
 }
@@ -0,0 +1,188 @@
+use termion::event::Key;
+
+use super::error::GameError;
+
+/// something the player can do with a key
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Action {
+  MoveUp,
+  MoveDown,
+  MoveLeft,
+  MoveRight,
+  Select,
+  /// drop the last staged shot
+  Unselect,
+  Fire,
+  Undo,
+  /// describe the cell under the cursor
+  Inspect,
+  /// sweep the region around the cursor for ships instead of firing
+  Radar,
+  /// move one of your ships instead of firing
+  Maneuver,
+  /// turn the ship being moved a quarter
+  Rotate,
+  /// sink one of your own ships for extra shots next turn
+  Scuttle,
+  /// suggest a shot on the opponent board
+  Hint,
+  /// switch to the next color palette
+  Palette,
+  Help,
+  PlayAgain,
+  Replay,
+  /// leave the replay or drop all staged shots
+  Back,
+  Quit,
+}
+
+impl Action {
+  fn from_name(name: &str) -> Result<Self, String> {
+    match name {
+      "move_up" => Ok(Action::MoveUp),
+      "move_down" => Ok(Action::MoveDown),
+      "move_left" => Ok(Action::MoveLeft),
+      "move_right" => Ok(Action::MoveRight),
+      "select" => Ok(Action::Select),
+      "unselect" => Ok(Action::Unselect),
+      "fire" => Ok(Action::Fire),
+      "undo" => Ok(Action::Undo),
+      "inspect" => Ok(Action::Inspect),
+      "radar" => Ok(Action::Radar),
+      "maneuver" => Ok(Action::Maneuver),
+      "rotate" => Ok(Action::Rotate),
+      "scuttle" => Ok(Action::Scuttle),
+      "hint" => Ok(Action::Hint),
+      "palette" => Ok(Action::Palette),
+      "help" => Ok(Action::Help),
+      "play_again" => Ok(Action::PlayAgain),
+      "replay" => Ok(Action::Replay),
+      "back" => Ok(Action::Back),
+      "quit" => Ok(Action::Quit),
+      _ => Err(format!("unknown action {}", name)),
+    }
+  }
+}
+
+/// keys bound to actions, a key triggers at most one action
+#[derive(PartialEq, Debug, Clone)]
+pub struct KeyMap {
+  bindings: Vec<(Key, Action)>,
+}
+
+impl Default for KeyMap {
+  fn default() -> Self {
+    Self {
+      bindings: vec![
+        (Key::Up, Action::MoveUp),
+        (Key::Char('k'), Action::MoveUp),
+        (Key::Down, Action::MoveDown),
+        (Key::Char('j'), Action::MoveDown),
+        (Key::Left, Action::MoveLeft),
+        (Key::Char('h'), Action::MoveLeft),
+        (Key::Right, Action::MoveRight),
+        (Key::Char('l'), Action::MoveRight),
+        (Key::Char(' '), Action::Select),
+        (Key::Backspace, Action::Unselect),
+        (Key::Char('\n'), Action::Fire),
+        (Key::Char('u'), Action::Undo),
+        (Key::Char('i'), Action::Inspect),
+        (Key::Char('s'), Action::Radar),
+        (Key::Char('m'), Action::Maneuver),
+        (Key::Char('o'), Action::Rotate),
+        (Key::Char('x'), Action::Scuttle),
+        (Key::Char('n'), Action::Hint),
+        (Key::Char('c'), Action::Palette),
+        (Key::Char('?'), Action::Help),
+        (Key::Char('r'), Action::PlayAgain),
+        (Key::Char('v'), Action::Replay),
+        (Key::Esc, Action::Back),
+        (Key::Char('q'), Action::Quit),
+        (Key::Ctrl('c'), Action::Quit),
+      ],
+    }
+  }
+}
+
+impl KeyMap {
+  pub fn action(&self, key: Key) -> Option<Action> {
+    self
+      .bindings
+      .iter()
+      .find(|(k, _)| *k == key)
+      .map(|(_, action)| *action)
+  }
+
+  /// bind `key` to `action`, replacing what the key did before
+  pub fn bind(&mut self, key: Key, action: Action) {
+    self.bindings.retain(|(k, _)| *k != key);
+    self.bindings.push((key, action));
+  }
+
+  /// the default bindings plus one `action = key` line each, e.g. `fire = f`
+  pub fn from_config(config: &str) -> Result<Self, GameError> {
+    Self::parse_config(config).map_err(|msg| GameError::Config(format!("key bindings, {}", msg)))
+  }
+
+  fn parse_config(config: &str) -> Result<Self, String> {
+    let mut keymap = Self::default();
+    for line in config.lines().map(str::trim) {
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let (action, key) = line
+        .split_once('=')
+        .ok_or(format!("expected `action = key`, found {}", line))?;
+      keymap.bind(parse_key(key.trim())?, Action::from_name(action.trim())?);
+    }
+    Ok(keymap)
+  }
+}
+
+fn parse_key(name: &str) -> Result<Key, String> {
+  let mut chars = name.chars();
+  match (chars.next(), chars.next()) {
+    (Some(c), None) => return Ok(Key::Char(c)),
+    (None, _) => return Err("missing key".into()),
+    _ => {}
+  }
+  match name {
+    "up" => Ok(Key::Up),
+    "down" => Ok(Key::Down),
+    "left" => Ok(Key::Left),
+    "right" => Ok(Key::Right),
+    "enter" => Ok(Key::Char('\n')),
+    "space" => Ok(Key::Char(' ')),
+    "tab" => Ok(Key::Char('\t')),
+    "esc" => Ok(Key::Esc),
+    "backspace" => Ok(Key::Backspace),
+    _ => match name
+      .strip_prefix("ctrl-")
+      .map(|c| c.chars().collect::<Vec<_>>())
+    {
+      Some(c) if c.len() == 1 => Ok(Key::Ctrl(c[0])),
+      _ => Err(format!("unknown key {}", name)),
+    },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_keymap_from_config() {
+    let keymap =
+      KeyMap::from_config("# custom keys\nfire = f\n\nquit = ctrl-x\nmove_up=w").unwrap();
+    assert_eq!(keymap.action(Key::Char('f')), Some(Action::Fire));
+    assert_eq!(keymap.action(Key::Ctrl('x')), Some(Action::Quit));
+    assert_eq!(keymap.action(Key::Char('w')), Some(Action::MoveUp));
+    // defaults stay in place
+    assert_eq!(keymap.action(Key::Char('\n')), Some(Action::Fire));
+    assert_eq!(keymap.action(Key::Char('z')), None);
+
+    assert!(KeyMap::from_config("fire").is_err());
+    assert!(KeyMap::from_config("shoot = f").is_err());
+    assert!(KeyMap::from_config("fire = ctrl-").is_err());
+  }
+}
@@ -0,0 +1,7 @@
+//! the game rules, headless play and the state socket framing, shared by the binary, the
+//! benchmarks and tools reading the socket
+
+pub mod error;
+pub mod frame;
+pub mod game;
+pub mod sim;
@@ -1,26 +1,42 @@
 mod app;
+mod daily;
 mod event;
-mod game;
+mod keymap;
+mod profile;
+mod render;
+mod snapshot;
+mod text;
 mod ui;
 
 use std::{
-  error::Error,
   io::{self, stdout, Write},
+  path::PathBuf,
   time::Duration,
 };
 
 use app::App;
+use battleship_rs::{error, frame, game, sim};
+use daily::Day;
+use error::GameError;
 use event::{Event, Events};
-use game::{Difficulty, Rule};
+use game::{
+  diff_replays, parse_tracking, Board, Difficulty, Formation, Game, HardStrategy, Rule, ShipType,
+};
+use keymap::KeyMap;
+use profile::Profile;
+use snapshot::{GameSnapshot, StateSocket};
 use structopt::StructOpt;
 use termion::{
-  event::Key,
+  event::MouseEvent,
   input::MouseTerminal,
   raw::IntoRawMode,
   screen::{AlternateScreen, ToMainScreen},
 };
 use tui::{backend::TermionBackend, Terminal};
 
+/// time in ms between two ticks
+const TICK_RATE_MS: u64 = 250;
+
 #[derive(Debug, StructOpt)]
 #[structopt(name = "battleship-rs", about = "A Battleship game in Rust")]
 struct Opt {
@@ -30,49 +46,495 @@ struct Opt {
   /// Game rule
   #[structopt(short, long, possible_values = &Difficulty::variants(), case_insensitive = true, default_value = "Hard")]
   pub difficulty: Difficulty,
+  /// Shots per turn for the Barrage rule
+  #[structopt(short, long, default_value = "3")]
+  pub barrage_shots: usize,
+  /// Number of sunk ships needed to win, 1 up to the fleet size (defaults to all ships)
+  #[structopt(short, long)]
+  pub win_threshold: Option<usize>,
+  /// Handicap, show this many opponent ship cells as hit from the start
+  #[structopt(long, default_value = "0")]
+  pub reveal_count: usize,
+  /// Your name used in game messages
+  #[structopt(short, long)]
+  pub name: Option<String>,
+  /// Opponent name used in game messages
+  #[structopt(short, long)]
+  pub opponent: Option<String>,
+  /// Practice mode, allows undoing volleys
+  #[structopt(short, long)]
+  pub practice: bool,
+  /// Fog of war, sinking a ship doesn't reveal all of its cells
+  #[structopt(short, long)]
+  pub fog: bool,
+  /// Break volley messages down per ship
+  #[structopt(short, long)]
+  pub verbose: bool,
+  /// Keep a free cell around every ship
+  #[structopt(long)]
+  pub no_touching: bool,
+  /// Build each fleet from ships of this one type, like `I`
+  #[structopt(long)]
+  pub uniform_ship: Option<ShipType>,
+  /// Swap ships with cells touching only at a corner, like the X, for straight lines
+  #[structopt(long)]
+  pub no_diagonals: bool,
+  /// Give the opponent your fleet reflected left to right, so neither side has a placement advantage
+  #[structopt(long)]
+  pub mirror: bool,
+  /// Hits each ship cell takes before it's damaged
+  #[structopt(long, default_value = "1")]
+  pub armor: usize,
+  /// Once per game move an undamaged ship instead of firing
+  #[structopt(long)]
+  pub maneuver: bool,
+  /// Once per game sink one of your own ships for its size in extra shots next turn
+  #[structopt(long)]
+  pub scuttle: bool,
+  /// The hard computer only hunts next to one hit at a time, like a person would
+  #[structopt(long)]
+  pub fair: bool,
+  /// Rows and columns around a hit the hard computer fires at, 2 by default
+  #[structopt(long)]
+  pub jitter: Option<usize>,
+  /// The hard computer hunts where the most ships could still fit, mostly the center of the board
+  #[structopt(long)]
+  pub center_bias: bool,
+  /// The computer only remembers its latest shots this many cells deep, though it never repeats one
+  #[structopt(long)]
+  pub bot_memory: Option<usize>,
+  /// Arrange your fleet in a formation instead of at random
+  #[structopt(long, possible_values = &Formation::variants(), case_insensitive = true, conflicts_with_all = &["uniform-ship", "no-diagonals", "mirror", "no-touching"])]
+  pub formation: Option<Formation>,
+  /// Play against the opponent board from a board code
+  #[structopt(long)]
+  pub board: Option<String>,
+  /// Play against the opponent board from a file holding a board code
+  #[structopt(long, parse(from_os_str), conflicts_with = "board")]
+  pub board_file: Option<PathBuf>,
+  /// Start with the hits and misses from this file on your tracking board, one `C7 hit` or `D2 miss` per line
+  #[structopt(long, parse(from_os_str))]
+  pub tracking: Option<PathBuf>,
+  /// Play this many computer vs computer games and print the stats
+  #[structopt(long)]
+  pub simulate: Option<usize>,
+  /// Also write one line per simulated game to this CSV file
+  #[structopt(long, parse(from_os_str), requires = "simulate")]
+  pub csv: Option<PathBuf>,
+  /// Play one computer vs computer game without the UI and print the result as a line of JSON
+  #[structopt(long)]
+  pub report_json: bool,
+  /// Compare the volleys of two save files and print the first move where they differ
+  #[structopt(long, parse(from_os_str), number_of_values = 2, value_names = &["a", "b"])]
+  pub diff_replays: Vec<PathBuf>,
+  /// Base seed for simulated games
+  #[structopt(long, default_value = "0")]
+  pub seed: u64,
+  /// Publish the game state as length prefixed JSON on this unix socket after every volley
+  #[structopt(long, parse(from_os_str))]
+  pub state_socket: Option<PathBuf>,
+  /// Key bindings file with one `action = key` per line
+  #[structopt(long, parse(from_os_str))]
+  pub keymap: Option<PathBuf>,
+  /// Settings kept between runs, like the color palette
+  #[structopt(long, parse(from_os_str))]
+  pub profile: Option<PathBuf>,
+  /// Milliseconds the easy computer waits before firing back, adaptive waits 1.5 times and hard twice as long
+  #[structopt(long, default_value = "1000")]
+  pub bot_delay: u64,
+  /// Two players take turns at the same keyboard
+  #[structopt(long)]
+  pub hot_seat: bool,
+  /// Play with plain text prompts on stdin and stdout instead of the terminal UI
+  #[structopt(long, conflicts_with_all = &["hot-seat", "tutorial"])]
+  pub text: bool,
+  /// Play today's challenge, the same fleets and computer shots for everyone with the same settings
+  #[structopt(long, conflicts_with_all = &["save", "hot-seat"])]
+  pub daily: bool,
+  /// Walk through firing and sinking against a faintly shown enemy fleet
+  #[structopt(long, conflicts_with = "hot-seat")]
+  pub tutorial: bool,
+  /// Words instead of emoji, for screen readers
+  #[structopt(long)]
+  pub accessible: bool,
+  /// Draw the boards as compact grids with lines between the cells
+  #[structopt(long)]
+  pub grid_lines: bool,
+  /// After a volley with a miss, show what was known around the missed cell
+  #[structopt(long)]
+  pub explain_misses: bool,
+  /// Outline a box a cell wider than the opponent's last ship around it
+  #[structopt(long)]
+  pub reveal_last_ship: bool,
+  /// Move the cursor to the next cell not fired at after each volley
+  #[structopt(long)]
+  pub auto_advance: bool,
+  /// Resume the game from this file if it exists, and save it there on quit
+  #[structopt(long, parse(from_os_str))]
+  pub save: Option<PathBuf>,
+  /// Save the game to this file after every turn
+  #[structopt(long, parse(from_os_str))]
+  pub autosave: Option<PathBuf>,
+  /// Match clock like `120s` or `2m`, when it runs out whoever sank more ships wins
+  #[structopt(long, parse(try_from_str = parse_clock))]
+  pub blitz: Option<Duration>,
+  /// Draw inline instead of on the alternate screen, the last frame stays visible after quitting
+  #[structopt(long)]
+  pub no_altscreen: bool,
+}
+
+/// the game's output, switched to the alternate screen until it's dropped unless drawing inline
+fn screen<'a, W: Write + 'a>(out: W, altscreen: bool) -> Box<dyn Write + 'a> {
+  if altscreen {
+    Box::new(AlternateScreen::from(out))
+  } else {
+    Box::new(out)
+  }
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
+/// seconds, with an optional `s` or `m` suffix
+fn parse_clock(clock: &str) -> Result<Duration, String> {
+  let (number, unit) = match clock.strip_suffix('m') {
+    Some(minutes) => (minutes, 60),
+    None => (clock.strip_suffix('s').unwrap_or(clock), 1),
+  };
+  number
+    .parse::<u64>()
+    .map(|n| Duration::from_secs(n * unit))
+    .map_err(|_| format!("invalid clock {}, expected e.g. 120s or 2m", clock))
+}
+
+fn main() {
+  if let Err(e) = run(Opt::from_args()) {
+    eprintln!("Error: {}", e);
+    std::process::exit(1);
+  }
+}
+
+fn run(opt: Opt) -> Result<(), GameError> {
+  if let Some(games) = opt.simulate {
+    let stats = sim::simulate_many(games, opt.rule, opt.difficulty, opt.seed);
+    println!(
+      "games: {} | player 1 wins: {:.1}% | player 2 wins: {:.1}% | average turns: {:.1} | wasted shots per game: {:.1} / {:.1}",
+      stats.games,
+      stats.win_rate(0) * 100.0,
+      stats.win_rate(1) * 100.0,
+      stats.average_turns(),
+      stats.average_wasted_shots(0),
+      stats.average_wasted_shots(1)
+    );
+    if let Some(path) = &opt.csv {
+      stats.write_csv(path)?;
+    }
+    return Ok(());
+  }
+  if opt.report_json {
+    let report = sim::play_out(Game::with_seed(opt.rule, opt.difficulty, opt.seed));
+    println!("{}", report.to_json());
+    return Ok(());
+  }
+  if let [a, b] = &opt.diff_replays[..] {
+    let (a, b) = (Game::load_from_path(a)?, Game::load_from_path(b)?);
+    match diff_replays(a.history(), b.history()) {
+      Some(index) => println!("replays diverge at move {}", index + 1),
+      None => println!("replays match"),
+    }
+    return Ok(());
+  }
+
+  let today = Day::today();
+  let mut game = match &opt.save {
+    Some(path) if path.exists() => Game::load_from_path(path)?,
+    _ => {
+      let mut game = if opt.daily {
+        Game::with_seed(opt.rule, opt.difficulty, today.seed())
+      } else {
+        Game::new(opt.rule, opt.difficulty)
+      };
+      game.barrage_shots = opt.barrage_shots;
+      game.reveal_full_ship_on_kill = !opt.fog;
+      game.allow_maneuver = opt.maneuver;
+      game.allow_scuttle = opt.scuttle;
+      if opt.no_touching {
+        game.set_no_touching(true);
+      }
+      let mut fleet = opt.uniform_ship.as_ref().map(ShipType::uniform_fleet);
+      if opt.no_diagonals {
+        let ships = fleet.unwrap_or_else(|| ShipType::get_initial_ships().to_vec());
+        fleet = Some(ShipType::without_diagonals(&ships));
+      }
+      if let Some(fleet) = fleet {
+        game.set_fleet(fleet)?;
+      }
+      game.set_win_threshold(opt.win_threshold)?;
+      if opt.mirror {
+        game.set_mirrored(true);
+      }
+      if opt.armor > 1 {
+        game.set_armor(opt.armor);
+      }
+      if opt.reveal_count > 0 {
+        game.set_reveal_count(opt.reveal_count);
+      }
+      if let Some(formation) = opt.formation {
+        game.set_formation(formation)?;
+      }
+      if let Some(code) = &opt.board {
+        game.set_computer_board(Board::from_code(code)?);
+      }
+      if let Some(path) = &opt.board_file {
+        game.set_computer_board(Board::load_from_path(path)?);
+      }
+      if let Some(path) = &opt.tracking {
+        let entries =
+          parse_tracking(&std::fs::read_to_string(path)?).map_err(GameError::Tracking)?;
+        game
+          .preseed_tracking(&entries)
+          .map_err(GameError::Tracking)?;
+      }
+      game
+    }
+  };
+  if let Some(strategy) = hard_strategy(&opt, game.difficulty())? {
+    game.strategy = Box::new(strategy);
+  }
+  let hot_seat = opt.hot_seat;
+  let default_name = |name: &str| hot_seat.then(|| name.to_string());
+  game
+    .player_mut()
+    .set_name(opt.name.or_else(|| default_name("Player 1")));
+  game
+    .computer_mut()
+    .set_name(opt.opponent.or_else(|| default_name("Player 2")));
+  game.practice = opt.practice;
+  game.verbose = opt.verbose;
+  game.bot_memory = opt.bot_memory;
+  if opt.text {
+    text::play(&mut game, io::stdin().lock(), &mut io::stdout())?;
+    return Ok(());
+  }
+  let keymap = match &opt.keymap {
+    Some(path) => KeyMap::from_config(&std::fs::read_to_string(path)?)?,
+    None => KeyMap::default(),
+  };
+  let mut profile = match &opt.profile {
+    Some(path) => Profile::load(path)?,
+    None => Profile::default(),
+  };
+  let mut state_socket = match &opt.state_socket {
+    Some(path) => Some(StateSocket::bind(path)?),
+    None => None,
+  };
+
+  let altscreen = !opt.no_altscreen;
   std::panic::set_hook(Box::new(move |x| {
     stdout()
       .into_raw_mode()
       .unwrap()
       .suspend_raw_mode()
       .unwrap();
-    write!(stdout().into_raw_mode().unwrap(), "{}", ToMainScreen).unwrap();
+    if altscreen {
+      write!(stdout().into_raw_mode().unwrap(), "{}", ToMainScreen).unwrap();
+    }
     print!("{:?}", x);
   }));
 
-  let opt = Opt::from_args();
-
-  // time in ms between two ticks is 250ms.
-  let events = Events::new(Duration::from_millis(250));
+  let events = Events::new(Duration::from_millis(TICK_RATE_MS));
 
-  let stdout = io::stdout().into_raw_mode()?;
+  let stdout = io::stdout().into_raw_mode().map_err(GameError::Terminal)?;
   let stdout = MouseTerminal::from(stdout);
-  let stdout = AlternateScreen::from(stdout);
-  let backend = TermionBackend::new(stdout);
-  let mut terminal = Terminal::new(backend)?;
+  let backend = TermionBackend::new(screen(stdout, altscreen));
+  let mut terminal = Terminal::new(backend).map_err(GameError::Terminal)?;
+  if !altscreen {
+    // start from a blank screen, what was there before scrolls away
+    terminal.clear().map_err(GameError::Terminal)?;
+  }
 
-  let mut app = App::new(" 🚀 Battleship.rs 🚀 ".into(), opt.rule, opt.difficulty);
+  let title = if opt.accessible {
+    " Battleship.rs "
+  } else {
+    " 🚀 Battleship.rs 🚀 "
+  };
+  let mut app = App::new(title.into(), game);
+  app.accessible = opt.accessible;
+  app.grid_lines = opt.grid_lines;
+  app.hot_seat = opt.hot_seat;
+  if opt.tutorial {
+    app.start_tutorial();
+  }
+  app.keymap = keymap;
+  app.auto_advance = opt.auto_advance;
+  app.explain_misses = opt.explain_misses;
+  app.reveal_last_ship = opt.reveal_last_ship;
+  app.autosave = opt.autosave.clone();
+  app.blitz = opt.blitz;
+  app.palette = profile.palette;
+  app.bot_delay = (opt.bot_delay / TICK_RATE_MS).min(u16::MAX as u64) as u16;
+  let mut disconnected = false;
+  let mut volleys = app.volley_count();
   loop {
-    terminal.draw(|f| ui::draw(f, &mut app))?;
+    terminal
+      .draw(|f| ui::draw(f, &mut app))
+      .map_err(GameError::Terminal)?;
 
-    match events.next()? {
-      Event::Input(key) => match key {
-        Key::Ctrl('c') | Key::Char('q') => {
-          app.should_quit = true;
+    match events.next() {
+      Event::Input(key) => app.on_key(key),
+      Event::Mouse(MouseEvent::Press(button, x, y)) => {
+        let size = terminal.size().map_err(GameError::Terminal)?;
+        // termion counts from 1
+        let at = ui::cell_at(
+          size,
+          app.accessible,
+          app.grid_lines,
+          x.saturating_sub(1),
+          y.saturating_sub(1),
+        );
+        if let Some((board, at)) = at {
+          app.on_click(button, board == 0, at);
         }
-        _ => app.on_key(key),
-      },
+      }
+      Event::Mouse(_) => {}
       Event::Tick => {
         app.on_tick();
       }
+      Event::Disconnect => {
+        disconnected = true;
+        app.should_quit = true;
+      }
+    }
+    if let Some(socket) = &mut state_socket {
+      if app.volley_count() != volleys {
+        volleys = app.volley_count();
+        // the game goes on without the subscriber, only tell the player
+        if let Err(e) = socket.broadcast(&GameSnapshot::new(&app)) {
+          app.message = e.to_string();
+        }
+      }
     }
     if app.should_quit {
       break;
     }
   }
 
+  if !altscreen {
+    // leave the last frame in place and continue below it
+    let bottom = terminal.size().map_err(GameError::Terminal)?.bottom();
+    terminal
+      .set_cursor(0, bottom.saturating_sub(1))
+      .map_err(GameError::Terminal)?;
+  }
+  // restore the main screen before reporting
+  drop(terminal);
+  if !altscreen {
+    println!();
+  }
+  if disconnected {
+    eprintln!("Input disconnected, exiting");
+  }
+  println!("{}", render::exit_summary(&app));
+  if app.is_won() {
+    println!("Opponent board code: {}", app.board_code());
+  }
+  if opt.daily && app.is_won() {
+    let result = profile.record_daily(today, app.winner() == Some(0), app.total_shots()[0]);
+    println!(
+      "Daily challenge {}: {} in {} shots, streak {}",
+      result.day,
+      if result.won { "won" } else { "lost" },
+      result.shots,
+      result.streak
+    );
+  }
+  if let Some(path) = &opt.profile {
+    Profile {
+      palette: app.palette,
+      ..profile
+    }
+    .save(path)?;
+  }
+  if let Some(path) = &opt.save {
+    if app.is_won() {
+      // a finished game has nothing left to resume
+      if path.exists() {
+        std::fs::remove_file(path)?;
+      }
+    } else {
+      app.save(path)?;
+    }
+  }
+
   Ok(())
 }
+
+/// the hard computer's tuning from the options, other difficulties refuse them
+fn hard_strategy(opt: &Opt, difficulty: Difficulty) -> Result<Option<HardStrategy>, GameError> {
+  if difficulty == Difficulty::Hard {
+    return Ok(Some(HardStrategy {
+      fair: opt.fair,
+      jitter: opt.jitter.unwrap_or(HardStrategy::default().jitter),
+      center_bias: opt.center_bias,
+    }));
+  }
+  let hard_only = [
+    ("--fair", opt.fair),
+    ("--jitter", opt.jitter.is_some()),
+    ("--center-bias", opt.center_bias),
+  ];
+  match hard_only.iter().find(|(_, set)| *set) {
+    Some((flag, _)) => Err(GameError::Config(format!(
+      "{} needs --difficulty hard",
+      flag
+    ))),
+    None => Ok(None),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use termion::screen::ToAlternateScreen;
+
+  use super::*;
+
+  #[test]
+  fn test_screen() {
+    let mut out = vec![];
+    write!(screen(&mut out, true), "frame").unwrap();
+    assert_eq!(
+      String::from_utf8(out).unwrap(),
+      format!("{}frame{}", ToAlternateScreen, ToMainScreen)
+    );
+
+    let mut out = vec![];
+    write!(screen(&mut out, false), "frame").unwrap();
+    assert_eq!(out, b"frame");
+  }
+
+  #[test]
+  fn test_hard_strategy() {
+    let opt = |args: &[&str]| Opt::from_iter([&["battleship-rs"], args].concat());
+    let strategy = hard_strategy(&opt(&["--fair", "--jitter", "3"]), Difficulty::Hard)
+      .unwrap()
+      .unwrap();
+    assert!(strategy.fair);
+    assert_eq!(strategy.jitter, 3);
+    assert!(!strategy.center_bias);
+    assert_eq!(
+      hard_strategy(&opt(&[]), Difficulty::Hard)
+        .unwrap()
+        .unwrap()
+        .jitter,
+      HardStrategy::default().jitter
+    );
+
+    // other difficulties don't silently drop the hard tuning
+    assert!(hard_strategy(&opt(&[]), Difficulty::Easy)
+      .unwrap()
+      .is_none());
+    for args in [&["--fair"][..], &["--jitter", "1"], &["--center-bias"]] {
+      assert!(matches!(
+        hard_strategy(&opt(args), Difficulty::Adaptive),
+        Err(GameError::Config(msg)) if msg == format!("{} needs --difficulty hard", args[0])
+      ));
+    }
+  }
+}
@@ -0,0 +1,153 @@
+use std::{fs, io, path::Path};
+
+use super::{daily::Day, error::GameError, render::Palette};
+
+/// settings kept between runs, one `name = value` per line
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct Profile {
+  pub palette: Palette,
+  /// the last daily challenge played
+  pub daily: Option<DailyResult>,
+}
+
+/// how a daily challenge went, written as `2026-10-16 won 42 3`
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct DailyResult {
+  pub day: Day,
+  pub won: bool,
+  pub shots: usize,
+  /// daily challenges won in a row, this one included
+  pub streak: usize,
+}
+
+impl DailyResult {
+  fn to_text(self) -> String {
+    let outcome = if self.won { "won" } else { "lost" };
+    format!("{} {} {} {}", self.day, outcome, self.shots, self.streak)
+  }
+
+  fn parse(text: &str) -> Result<Self, String> {
+    let invalid = || format!("invalid daily result {}", text);
+    match text.split_whitespace().collect::<Vec<_>>()[..] {
+      [day, outcome, shots, streak] => Ok(Self {
+        day: day.parse()?,
+        won: match outcome {
+          "won" => true,
+          "lost" => false,
+          _ => return Err(invalid()),
+        },
+        shots: shots.parse().map_err(|_| invalid())?,
+        streak: streak.parse().map_err(|_| invalid())?,
+      }),
+      _ => Err(invalid()),
+    }
+  }
+}
+
+impl Profile {
+  /// a missing file is a fresh profile
+  pub fn load(path: &Path) -> Result<Self, GameError> {
+    match fs::read_to_string(path) {
+      Ok(text) => Self::parse(&text).map_err(GameError::Profile),
+      Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+      Err(e) => Err(GameError::Io(e)),
+    }
+  }
+
+  pub fn save(&self, path: &Path) -> Result<(), GameError> {
+    fs::write(path, self.to_text())?;
+    Ok(())
+  }
+
+  /// keep the first result of a day, a win the day after a win extends the streak
+  pub fn record_daily(&mut self, day: Day, won: bool, shots: usize) -> DailyResult {
+    let last = self.daily.filter(|last| last.day <= day);
+    if let Some(last) = last.filter(|last| last.day == day) {
+      return last;
+    }
+    let streak = match last {
+      _ if !won => 0,
+      Some(last) if last.won && last.day.next() == day => last.streak + 1,
+      _ => 1,
+    };
+    let result = DailyResult {
+      day,
+      won,
+      shots,
+      streak,
+    };
+    self.daily = Some(result);
+    result
+  }
+
+  fn to_text(self) -> String {
+    let mut text = format!("palette = {}\n", self.palette.name());
+    if let Some(daily) = self.daily {
+      text += &format!("daily = {}\n", daily.to_text());
+    }
+    text
+  }
+
+  fn parse(text: &str) -> Result<Self, String> {
+    let mut profile = Self::default();
+    for line in text.lines().map(str::trim) {
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let (name, value) = line
+        .split_once('=')
+        .ok_or(format!("expected `name = value`, found {}", line))?;
+      match name.trim() {
+        "palette" => profile.palette = value.trim().parse()?,
+        "daily" => profile.daily = Some(DailyResult::parse(value.trim())?),
+        name => return Err(format!("unknown setting {}", name)),
+      }
+    }
+    Ok(profile)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_profile() {
+    let mut profile = Profile {
+      palette: Palette::Monochrome,
+      daily: None,
+    };
+    assert_eq!(Profile::parse(&profile.to_text()), Ok(profile));
+    profile.record_daily("2026-10-16".parse().unwrap(), true, 42);
+    assert_eq!(Profile::parse(&profile.to_text()), Ok(profile));
+    assert_eq!(Profile::parse("# empty\n"), Ok(Profile::default()));
+    assert!(Profile::parse("palette = neon").is_err());
+    assert!(Profile::parse("volume = 11").is_err());
+
+    let path = std::env::temp_dir().join(format!("battleship-{}.profile", std::process::id()));
+    assert_eq!(Profile::load(&path).unwrap(), Profile::default());
+    profile.save(&path).unwrap();
+    assert_eq!(Profile::load(&path).unwrap(), profile);
+    std::fs::remove_file(&path).unwrap();
+  }
+
+  #[test]
+  fn test_profile_record_daily() {
+    let mut profile = Profile::default();
+    let day = "2026-10-16".parse::<Day>().unwrap();
+    assert_eq!(profile.record_daily(day, true, 40).streak, 1);
+    // playing the same day again doesn't count
+    assert_eq!(profile.record_daily(day, true, 30).shots, 40);
+    assert_eq!(profile.record_daily(day.next(), true, 35).streak, 2);
+    assert_eq!(profile.record_daily(day.next().next(), false, 60).streak, 0);
+    assert_eq!(
+      profile
+        .record_daily(day.next().next().next(), true, 50)
+        .streak,
+      1
+    );
+    // a skipped day starts over
+    let later = day.next().next().next().next().next();
+    assert_eq!(profile.record_daily(later, true, 50).streak, 1);
+  }
+}
@@ -0,0 +1,593 @@
+use std::str::FromStr;
+
+use super::{
+  app::App,
+  game::{
+    Board, Clue, Coord, Coordinate, Outcome, ShipType, ShotAnalysis, Sinking, Status, Volley, COLS,
+    ROWS,
+  },
+};
+
+/// volleys listed in the history panel
+const HISTORY_LENGTH: usize = 12;
+
+/// what a frontend needs to draw a single cell
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct CellView {
+  pub status: Status,
+  pub active: bool,
+  pub pending: bool,
+  /// the suggested shot
+  pub hinted: bool,
+  /// an opponent ship faintly shown during the tutorial
+  pub ghost: bool,
+  /// a cell of the ship being moved, and whether it fits there
+  pub preview: Option<bool>,
+  /// inside the box around the opponent's last ship
+  pub region: bool,
+}
+
+/// cell colors, cycled while playing
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum Palette {
+  #[default]
+  Default,
+  /// no red against green, for colorblind players
+  HighContrast,
+  /// shades of gray only
+  Monochrome,
+}
+
+impl Palette {
+  pub fn next(self) -> Self {
+    match self {
+      Palette::Default => Palette::HighContrast,
+      Palette::HighContrast => Palette::Monochrome,
+      Palette::Monochrome => Palette::Default,
+    }
+  }
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Palette::Default => "default",
+      Palette::HighContrast => "high-contrast",
+      Palette::Monochrome => "monochrome",
+    }
+  }
+}
+
+impl FromStr for Palette {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    [Palette::Default, Palette::HighContrast, Palette::Monochrome]
+      .iter()
+      .copied()
+      .find(|palette| palette.name() == s)
+      .ok_or_else(|| format!("unknown palette {}", s))
+  }
+}
+
+/// a board with its title, rows of cells
+#[derive(PartialEq, Debug, Clone)]
+pub struct GridView {
+  pub title: String,
+  pub cells: Vec<Vec<CellView>>,
+}
+
+const LEGEND: [(Status, &str); 4] = [
+  (Status::Live, "ship"),
+  (Status::Miss, "missed shot"),
+  (Status::Hit, "hit ship"),
+  (Status::Kill, "sunk ship"),
+];
+
+/// everything drawn in one frame, independent of the terminal library
+#[derive(PartialEq, Debug, Clone)]
+pub struct Screen {
+  pub title: String,
+  pub help: String,
+  pub grids: [GridView; 2],
+  pub status: String,
+  /// the last status message with its age, stays up after the alert fades
+  pub last_message: String,
+  /// rule and legend help, shown on top of everything else
+  pub overlay: Option<String>,
+  /// winner and final stats once the game is over
+  pub result: Option<String>,
+  /// words instead of emoji for screen readers
+  pub accessible: bool,
+  /// one grid per board with lines between the cells instead of a box per cell
+  pub grid_lines: bool,
+  /// the latest volleys, oldest first
+  pub history: Vec<String>,
+  /// opponent ships hit so far, shown above the history
+  pub ships: Vec<String>,
+  pub palette: Palette,
+}
+
+/// a frontend able to draw a `Screen`, the tui one lives in `ui`
+pub trait Renderer {
+  fn render(&mut self, screen: &Screen);
+}
+
+impl Screen {
+  pub fn new(app: &App) -> Self {
+    Self {
+      title: format!(
+        "{} | Rule: {} ({}s){}{}",
+        app.title,
+        app.rule(),
+        app.elapsed_duration(),
+        match app.clock_left() {
+          Some(left) if !app.is_won() => format!(" | Clock: {}s", left.as_secs()),
+          _ => String::default(),
+        },
+        match app.replay_progress() {
+          Some((step, len)) => format!(" | Replay {}/{}", step, len),
+          None if app.is_won() => String::default(),
+          None => format!(" | Shots left: {}", app.shots_left()),
+        }
+      ),
+      help: if app.is_replay() {
+        "step: 🠔 🠖 (or) hl | autoplay: <space> | back: <v> | quit: <q>".to_string()
+      } else if app.is_game_over() {
+        "play again: <r> | replay: <v> | help: <?> | quit: <q>".to_string()
+      } else {
+        format!(
+          "{}move: 🠔 🠗 🠕 🠖 (or) hjkl | select/unselect: <space> | drop last/all: <backspace>/<esc> | fire: <enter>{} | inspect: <i> | radar ({} left): <s> <enter> | hint ({} left): <n>{}{} | colors: <c> | help: <?> | quit: <q>",
+          app
+            .tutorial_prompt()
+            .map(|prompt| format!("Tutorial: {} | ", prompt))
+            .unwrap_or_default(),
+          if app.is_practice() {
+            " | undo: <u>"
+          } else {
+            ""
+          },
+          app.radar_sweeps_left(),
+          app.hints_left(),
+          if app.can_maneuver() {
+            " | move a ship: <m> <m>, turn it: <o>"
+          } else {
+            ""
+          },
+          if app.can_scuttle() {
+            " | scuttle a ship: <x> <enter>"
+          } else {
+            ""
+          }
+        )
+      },
+      grids: [
+        grid_view(app, app.viewer_names()[0], true),
+        grid_view(app, app.viewer_names()[1], false),
+      ],
+      // the result screen replaces the last alert
+      status: if app.is_game_over() {
+        String::default()
+      } else {
+        app.message.clone()
+      },
+      last_message: match app.last_message() {
+        Some(_) if app.is_replay() => String::default(),
+        Some((message, 0)) => message.to_string(),
+        Some((message, 1)) => format!("{} (1 turn ago)", message),
+        Some((message, age)) => format!("{} ({} turns ago)", message, age),
+        None => String::default(),
+      },
+      overlay: if app.is_quit_pending() {
+        Some("Quit? (y/n)".to_string())
+      } else if app.is_help_shown() {
+        Some(help_text(app))
+      } else if let Some(name) = app.handing_off_to() {
+        Some(format!(
+          "Pass the keyboard to {}\n\npress any key to show your boards",
+          name
+        ))
+      } else {
+        app
+          .miss_clues()
+          .map(|(at, clues)| miss_text(&at, &clues, app.accessible))
+      },
+      result: if app.is_game_over() {
+        Some(result_text(app))
+      } else {
+        None
+      },
+      accessible: app.accessible,
+      grid_lines: app.grid_lines,
+      history: history_lines(app),
+      ships: app.ship_status(),
+      palette: app.palette,
+    }
+  }
+}
+
+fn help_text(app: &App) -> String {
+  let rule = app.rule();
+  std::iter::once(format!("Rule {}: {}", rule, rule.description()))
+    .chain(std::iter::once(String::default()))
+    .chain(std::iter::once(format!(
+      "Fleet: {} | {:.0}% of the board",
+      ShipType::get_initial_ships()
+        .iter()
+        .map(|s| format!("{} ({} cells)", s.name(), s.cell_count()))
+        .collect::<Vec<_>>()
+        .join(", "),
+      app.fleet(0).occupancy() * 100.0
+    )))
+    .chain(
+      LEGEND
+        .iter()
+        .map(|(status, label)| format!("{} {}", status.label(app.accessible), label)),
+    )
+    .chain(std::iter::once(String::default()))
+    .chain(std::iter::once("press any key to close".to_string()))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// the cells around a missed shot as a small grid, with a count of what they showed
+fn miss_text(at: &Coord, clues: &[(Coord, Clue)], accessible: bool) -> String {
+  let label = |clue: Clue| match clue {
+    Clue::Open if accessible => "open".to_string(),
+    Clue::Open => "·".to_string(),
+    Clue::Miss => Status::Miss.label(accessible),
+    Clue::Hit => Status::Hit.label(accessible),
+    Clue::Wreck => Status::Kill.label(accessible),
+  };
+  let (row, col) = Coordinate::from(*at);
+  let grid = (row.saturating_sub(1)..=(row + 1).min(ROWS - 1)).map(|r| {
+    (col.saturating_sub(1)..=(col + 1).min(COLS - 1))
+      .map(|c| {
+        match clues
+          .iter()
+          .find(|(near, _)| Coordinate::from(*near) == (r, c))
+        {
+          Some((_, clue)) => label(*clue),
+          None if accessible => "shot".to_string(),
+          None => "◎".to_string(),
+        }
+      })
+      .collect::<Vec<_>>()
+      .join(" ")
+  });
+  let count = |clue: Clue| clues.iter().filter(|(_, c)| *c == clue).count();
+  std::iter::once(format!("Missed {}, what was known around it:", at))
+    .chain(std::iter::once(String::default()))
+    .chain(grid)
+    .chain(std::iter::once(String::default()))
+    .chain(std::iter::once(format!(
+      "{} missed | {} hit afloat | {} sunk | {} not fired",
+      count(Clue::Miss),
+      count(Clue::Hit),
+      count(Clue::Wreck),
+      count(Clue::Open)
+    )))
+    .chain(std::iter::once(if count(Clue::Hit) > 0 {
+      "A hit next to it pointed at a ship nearby".to_string()
+    } else {
+      "No hit next to it, it was a guess".to_string()
+    }))
+    .chain(std::iter::once(String::default()))
+    .chain(std::iter::once("press any key to close".to_string()))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+fn result_text(app: &App) -> String {
+  let [player_sunk, computer_sunk] = app.ships_sunk();
+  [
+    match app.outcome() {
+      Some(Outcome::Draw) => "Draw".to_string(),
+      _ => format!("{} won", app.winner_name().unwrap_or_default()),
+    },
+    String::default(),
+    format!(
+      "Time: {}s | Volleys: {} | Average turn: {}",
+      app.elapsed_duration(),
+      app.volley_count(),
+      match app.average_turn_duration() {
+        Some(average) => format!("{:.1}s", average.as_secs_f64()),
+        None => "-".to_string(),
+      }
+    ),
+    format!(
+      "Ships sunk: {} {} | {} {}",
+      app.player_name(),
+      computer_sunk,
+      app.computer_name(),
+      player_sunk
+    ),
+    format!(
+      "Shots fired: {} {} | {} {}",
+      app.player_name(),
+      app.total_shots()[0],
+      app.computer_name(),
+      app.total_shots()[1]
+    ),
+    format!(
+      "Efficiency: {} {} | {} {}",
+      app.player_name(),
+      efficiency_text(&app.shot_analysis()[0]),
+      app.computer_name(),
+      efficiency_text(&app.shot_analysis()[1])
+    ),
+    format!(
+      "Score: {} {} | {} {}",
+      app.player_name(),
+      app.scores()[0],
+      app.computer_name(),
+      app.scores()[1]
+    ),
+    String::default(),
+    "play again: <r> | replay: <v> | quit: <q>".to_string(),
+  ]
+  .join("\n")
+}
+
+/// like "20/50 40% (C)", the fewest shots for the ships sunk against the shots fired
+fn efficiency_text(analysis: &ShotAnalysis) -> String {
+  format!(
+    "{}/{} {:.0}% ({})",
+    analysis.min_shots,
+    analysis.actual_shots,
+    analysis.efficiency() * 100.0,
+    analysis.grade()
+  )
+}
+
+/// one line printed to the main screen on exit, for finished and abandoned games alike
+pub fn exit_summary(app: &App) -> String {
+  let [player, computer] = app.accuracy();
+  format!(
+    "{} | Accuracy: {} {:.0}% | {} {:.0}% | Volleys: {}",
+    match app.outcome() {
+      Some(Outcome::Draw) => "Draw".to_string(),
+      Some(Outcome::Win(_)) => format!("{} won", app.winner_name().unwrap_or_default()),
+      None => "Game abandoned".to_string(),
+    },
+    app.player_name(),
+    player * 100.0,
+    app.computer_name(),
+    computer * 100.0,
+    app.volley_count()
+  )
+}
+
+fn history_lines(app: &App) -> Vec<String> {
+  let names = [app.player_name(), app.computer_name()];
+  let lines = app
+    .history()
+    .iter()
+    .enumerate()
+    .flat_map(|(i, volley)| {
+      let shooter = names[volley.turn];
+      let target = names[1 - volley.turn];
+      std::iter::once(volley_line(volley, shooter, app.fleet(1 - volley.turn))).chain(
+        app
+          .sinkings()
+          .iter()
+          .filter(move |s| s.volley == i)
+          .map(move |s| sinking_line(s, shooter, target)),
+      )
+    })
+    .collect::<Vec<_>>();
+  lines[lines.len().saturating_sub(HISTORY_LENGTH)..].to_vec()
+}
+
+/// e.g. "Turn 12: You sank Computer's Line."
+fn sinking_line(sinking: &Sinking, shooter: &str, target: &str) -> String {
+  format!(
+    "Turn {}: {} sank {}'s {}.",
+    sinking.volley + 1,
+    shooter,
+    target,
+    sinking.ship_type.name()
+  )
+}
+
+/// e.g. "You → C7: hit, D4: miss", `target` is the fleet fired at
+fn volley_line(volley: &Volley, shooter: &str, target: &Board) -> String {
+  let ship_cells = target.ship_cells();
+  let shots = volley
+    .shots
+    .iter()
+    .map(|shot| {
+      let result = if ship_cells.contains(&Coordinate::from(*shot)) {
+        "hit"
+      } else {
+        "miss"
+      };
+      format!("{}: {}", shot, result)
+    })
+    .collect::<Vec<_>>()
+    .join(", ");
+  format!("{} → {}", shooter, shots)
+}
+
+fn grid_view(app: &App, title: &str, is_self: bool) -> GridView {
+  GridView {
+    title: title.to_string(),
+    cells: (0..ROWS)
+      .map(|r| {
+        (0..COLS)
+          .filter_map(|c| Coord::new(r, c))
+          .map(|c| app.cell(c, is_self).view())
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use termion::event::Key;
+
+  use super::*;
+  use crate::game::{Difficulty, Game, Rule};
+
+  #[derive(Default)]
+  struct MockRenderer {
+    screens: Vec<Screen>,
+  }
+
+  impl Renderer for MockRenderer {
+    fn render(&mut self, screen: &Screen) {
+      self.screens.push(screen.clone());
+    }
+  }
+
+  #[test]
+  fn test_volley_line() {
+    let target = Board::from_code("I.90.2.4").unwrap();
+    let at = |row, col| Coord::new(row, col).unwrap();
+    let volley = Volley {
+      turn: 1,
+      shots: [at(3, 5), at(0, 0)].iter().copied().collect(),
+    };
+    assert_eq!(
+      volley_line(&volley, "HAL", &target),
+      "HAL → A1: miss, D6: hit"
+    );
+
+    let sinking = Sinking {
+      volley: 11,
+      turn: 0,
+      ship_type: ShipType::I,
+    };
+    assert_eq!(
+      sinking_line(&sinking, "You", "HAL"),
+      "Turn 12: You sank HAL's Line."
+    );
+  }
+
+  #[test]
+  fn test_hot_seat_screen_follows_player() {
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    game.player_mut().set_name(Some("Alice".into()));
+    game.computer_mut().set_name(Some("Bob".into()));
+    let bob_fleet = game.computer().player_board().ship_cells();
+    let mut app = App::new("test".into(), game);
+    app.hot_seat = true;
+    let active = |screen: &Screen| {
+      (0..ROWS)
+        .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+        .filter(|(r, c)| screen.grids[1].cells[*r][*c].active)
+        .collect::<Vec<_>>()
+    };
+
+    app.on_key(Key::Right);
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    // boards stay hidden until Bob takes over
+    let screen = Screen::new(&app);
+    assert!(screen.overlay.unwrap().contains("Pass the keyboard to Bob"));
+    assert!(screen
+      .grids
+      .iter()
+      .flat_map(|g| g.cells.iter().flatten())
+      .all(|c| c.status == Status::Space && !c.active));
+    app.on_tick();
+    assert!(
+      app.history().len() == 1,
+      "the computer never fires in hot seat games"
+    );
+
+    app.on_key(Key::Char('x'));
+    let screen = Screen::new(&app);
+    assert!(screen.overlay.is_none());
+    assert_eq!(screen.grids[0].title, "Bob");
+    assert_eq!(screen.grids[1].title, "Alice");
+    assert_eq!(active(&screen), vec![(0, 0)]);
+    let live = (0..ROWS)
+      .flat_map(|r| (0..COLS).map(move |c| (r, c)))
+      .filter(|(r, c)| screen.grids[0].cells[*r][*c].status == Status::Live)
+      .collect::<Vec<_>>();
+    assert_eq!(live, bob_fleet);
+
+    app.on_key(Key::Down);
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    app.on_key(Key::Char('x'));
+    // Alice's cursor is where she left it
+    let screen = Screen::new(&app);
+    assert_eq!(screen.grids[0].title, "Alice");
+    assert_eq!(active(&screen), vec![(0, 1)]);
+  }
+
+  #[test]
+  fn test_exit_summary() {
+    let mut app = App::new(
+      "test".into(),
+      Game::with_seed(Rule::Default, Difficulty::Easy, 1),
+    );
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Char('\n'));
+    assert_eq!(
+      exit_summary(&app),
+      "Game abandoned | Accuracy: You 0% | Computer 0% | Volleys: 1"
+    );
+
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Hard, 2);
+    while !game.is_won() {
+      game.bot_fire();
+    }
+    let volleys = game.history().len();
+    let accuracy = [game.accuracy(0), game.accuracy(1)];
+    let app = App::new("test".into(), game);
+    assert_eq!(
+      exit_summary(&app),
+      format!(
+        "{} won | Accuracy: You {:.0}% | Computer {:.0}% | Volleys: {}",
+        app.winner_name().unwrap(),
+        accuracy[0] * 100.0,
+        accuracy[1] * 100.0,
+        volleys
+      )
+    );
+  }
+
+  #[test]
+  fn test_renderer_receives_screen() {
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Easy, 1);
+    game.player_mut().set_name(Some("Alice".into()));
+    let mut app = App::new("test".into(), game);
+    app.on_key(Key::Right);
+    app.on_key(Key::Char(' '));
+    app.on_key(Key::Down);
+
+    let mut renderer = MockRenderer::default();
+    renderer.render(&Screen::new(&app));
+
+    let screen = &renderer.screens[0];
+    assert!(screen.title.starts_with("test | Rule: Fury"));
+    assert!(screen.title.ends_with("Shots left: 3"));
+    assert_eq!(screen.grids[0].title, "Alice");
+    assert_eq!(screen.grids[1].title, "Computer");
+    assert_eq!(screen.status, app.message);
+    assert!(screen.overlay.is_none());
+    assert!(screen.result.is_none());
+    assert!(screen.history.is_empty());
+
+    let [player, opponent] = &screen.grids;
+    assert_eq!(player.cells.len(), ROWS);
+    assert!(player.cells.iter().all(|row| row.len() == COLS));
+    // own board shows the fleet, but never the cursor
+    assert!(player
+      .cells
+      .iter()
+      .flatten()
+      .any(|c| c.status == Status::Live));
+    assert!(!player.cells.iter().flatten().any(|c| c.active || c.pending));
+
+    assert!(opponent.cells[0][1].pending);
+    assert!(opponent.cells[1][1].active);
+    assert!(opponent
+      .cells
+      .iter()
+      .flatten()
+      .all(|c| c.status == Status::Space));
+  }
+}
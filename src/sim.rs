@@ -0,0 +1,293 @@
+use std::{fs, path::Path, thread};
+
+use serde::Serialize;
+
+use super::{
+  error::GameError,
+  game::{Coordinate, Difficulty, Game, Rule},
+};
+
+/// columns of `SimStats::to_csv`, scripts rely on their names and order
+const CSV_HEADER: &str =
+  "seed,winner,turns,human_accuracy,bot_accuracy,human_wasted_shots,bot_wasted_shots";
+
+/// aggregated results of bot vs bot games
+#[derive(PartialEq, Debug, Default, Clone)]
+pub struct SimStats {
+  pub games: usize,
+  pub wins: [usize; 2],
+  pub total_turns: usize,
+  /// shots per player fired more than one cell away from every ship
+  pub wasted_shots: [usize; 2],
+  /// every game on its own, in seed order
+  pub results: Vec<SimResult>,
+}
+
+/// one bot vs bot game, the first player stands in for the human
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct SimResult {
+  pub seed: u64,
+  pub winner: usize,
+  pub turns: usize,
+  pub accuracy: [f64; 2],
+  pub wasted_shots: [usize; 2],
+}
+
+impl SimStats {
+  fn merge(mut self, other: SimStats) -> Self {
+    self.games += other.games;
+    self.wins[0] += other.wins[0];
+    self.wins[1] += other.wins[1];
+    self.total_turns += other.total_turns;
+    self.wasted_shots[0] += other.wasted_shots[0];
+    self.wasted_shots[1] += other.wasted_shots[1];
+    self.results.extend(other.results);
+    self
+  }
+
+  /// a header line and one line per game
+  pub fn to_csv(&self) -> String {
+    std::iter::once(CSV_HEADER.to_string())
+      .chain(self.results.iter().map(|r| {
+        format!(
+          "{},{},{},{:.3},{:.3},{},{}",
+          r.seed,
+          r.winner,
+          r.turns,
+          r.accuracy[0],
+          r.accuracy[1],
+          r.wasted_shots[0],
+          r.wasted_shots[1]
+        )
+      }))
+      .map(|line| line + "\n")
+      .collect()
+  }
+
+  pub fn write_csv(&self, path: &Path) -> Result<(), GameError> {
+    fs::write(path, self.to_csv())?;
+    Ok(())
+  }
+
+  pub fn win_rate(&self, player: usize) -> f64 {
+    if self.games == 0 {
+      0.0
+    } else {
+      self.wins[player] as f64 / self.games as f64
+    }
+  }
+
+  pub fn average_turns(&self) -> f64 {
+    if self.games == 0 {
+      0.0
+    } else {
+      self.total_turns as f64 / self.games as f64
+    }
+  }
+
+  pub fn average_wasted_shots(&self, player: usize) -> f64 {
+    if self.games == 0 {
+      0.0
+    } else {
+      self.wasted_shots[player] as f64 / self.games as f64
+    }
+  }
+}
+
+/// how a finished game ended, for scripts
+#[derive(Debug, Clone, Serialize)]
+pub struct GameReport {
+  /// index of the winning player, none for a draw
+  pub winner: Option<usize>,
+  /// volleys fired by both players
+  pub turns: usize,
+  pub rule: Rule,
+}
+
+impl GameReport {
+  pub fn new(game: &Game) -> Self {
+    Self {
+      winner: game.winner(),
+      turns: game.history().len(),
+      rule: game.rule,
+    }
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(self).unwrap_or_default()
+  }
+}
+
+/// play a bot vs bot game to the end
+pub fn play_out(mut game: Game) -> GameReport {
+  while !game.is_won() {
+    game.bot_fire();
+  }
+  GameReport::new(&game)
+}
+
+/// play a single bot vs bot game
+pub fn simulate(rule: Rule, difficulty: Difficulty, seed: u64) -> SimStats {
+  let mut game = Game::with_seed(rule, difficulty, seed);
+  let mut turns = 0;
+  while game.winner().is_none() {
+    game.bot_fire();
+    turns += 1;
+  }
+  let winner = game.winner().unwrap_or_default();
+  let mut wins = [0; 2];
+  wins[winner] = 1;
+  let wasted_shots = wasted_shots(&game);
+  SimStats {
+    games: 1,
+    wins,
+    total_turns: turns,
+    wasted_shots,
+    results: vec![SimResult {
+      seed,
+      winner,
+      turns,
+      accuracy: [game.accuracy(0), game.accuracy(1)],
+      wasted_shots,
+    }],
+  }
+}
+
+/// shots per player that landed more than one cell away from every ship of the opponent
+pub fn wasted_shots(game: &Game) -> [usize; 2] {
+  let boards = [game.player().player_board(), game.computer().player_board()];
+  let mut wasted = [0; 2];
+  for volley in game.history() {
+    wasted[volley.turn] += volley
+      .shots
+      .iter()
+      .filter(|shot| !boards[1 - volley.turn].is_near_ship(Coordinate::from(**shot)))
+      .count();
+  }
+  wasted
+}
+
+/// play `n` games across threads, game `i` is seeded with `base_seed + i`
+pub fn simulate_many(n: usize, rule: Rule, difficulty: Difficulty, base_seed: u64) -> SimStats {
+  let workers = thread::available_parallelism()
+    .map_or(1, |p| p.get())
+    .min(n)
+    .max(1);
+
+  let handles = (0..workers)
+    .map(|worker| {
+      thread::spawn(move || {
+        (worker..n)
+          .step_by(workers)
+          .map(|i| simulate(rule, difficulty, base_seed.wrapping_add(i as u64)))
+          .fold(SimStats::default(), SimStats::merge)
+      })
+    })
+    .collect::<Vec<_>>();
+
+  let mut stats = handles
+    .into_iter()
+    .map(|h| h.join().expect("simulation thread panicked"))
+    .fold(SimStats::default(), SimStats::merge);
+  // each worker took every n-th game, put them back in the order they were seeded
+  stats
+    .results
+    .sort_by_key(|r| r.seed.wrapping_sub(base_seed));
+  stats
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::game::{Board, Coord, COLS, ROWS};
+
+  #[test]
+  fn test_simulate_many_reproducible() {
+    let stats = simulate_many(20, Rule::Fury, Difficulty::Hard, 42);
+    assert_eq!(stats.games, 20);
+    assert_eq!(stats.wins[0] + stats.wins[1], 20);
+    assert!(stats.average_turns() > 0.0);
+
+    assert_eq!(simulate_many(20, Rule::Fury, Difficulty::Hard, 42), stats);
+  }
+
+  #[test]
+  fn test_sim_stats_csv() {
+    let stats = simulate_many(2, Rule::Fury, Difficulty::Hard, u64::MAX);
+    let csv = stats.to_csv();
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(
+      lines[0],
+      "seed,winner,turns,human_accuracy,bot_accuracy,human_wasted_shots,bot_wasted_shots"
+    );
+    // seeds wrap around and stay in game order
+    assert!(lines[1].starts_with(&format!("{},", u64::MAX)));
+    assert!(lines[2].starts_with("0,"));
+    assert!(lines[1..].iter().all(|l| l.split(',').count() == 7));
+
+    let path = std::env::temp_dir().join(format!("battleship-sim-{}.csv", std::process::id()));
+    stats.write_csv(&path).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), csv);
+    let _ = std::fs::remove_file(&path);
+  }
+
+  #[test]
+  fn test_game_report_json() {
+    let report = GameReport {
+      winner: Some(0),
+      turns: 37,
+      rule: Rule::Fury,
+    };
+    assert_eq!(report.to_json(), r#"{"winner":0,"turns":37,"rule":"Fury"}"#);
+
+    let report = play_out(Game::with_seed(Rule::Charge, Difficulty::Hard, 7));
+    assert!(report.winner.is_some());
+    assert_eq!(
+      report.to_json(),
+      format!(
+        r#"{{"winner":{},"turns":{},"rule":"Charge"}}"#,
+        report.winner.unwrap(),
+        report.turns
+      )
+    );
+    // the same game as the simulation
+    assert_eq!(
+      report.turns,
+      simulate(Rule::Charge, Difficulty::Hard, 7).total_turns
+    );
+  }
+
+  #[test]
+  fn test_wasted_shots() {
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    let player_board = game.player().player_board().clone();
+    let computer_board = game.computer().player_board().clone();
+    let computer_cells = computer_board.ship_cells();
+    let cells = || (0..ROWS).flat_map(|r| (0..COLS).map(move |c| (r, c)));
+    let far_from = |board: &Board| cells().find(|shot| !board.is_near_ship(*shot)).unwrap();
+    // a miss right next to the ship cell
+    let next_to = |cell: Coordinate| {
+      cells()
+        .find(|shot| {
+          shot.0.abs_diff(cell.0) <= 1
+            && shot.1.abs_diff(cell.1) <= 1
+            && !computer_cells.contains(shot)
+        })
+        .unwrap()
+    };
+    let fire = |game: &mut Game, shot: Coordinate| {
+      let shots = std::iter::once(Coord::new(shot.0, shot.1).unwrap()).collect();
+      game.fire(&shots, !game.is_user_turn());
+    };
+
+    // player: a hit, a near miss, a wasted shot; computer: two wasted shots
+    fire(&mut game, computer_cells[0]);
+    fire(&mut game, far_from(&player_board));
+    fire(&mut game, next_to(computer_cells[0]));
+    fire(&mut game, far_from(&player_board));
+    fire(&mut game, far_from(&computer_board));
+    assert_eq!(wasted_shots(&game), [1, 2]);
+  }
+}
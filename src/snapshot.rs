@@ -0,0 +1,297 @@
+use std::{
+  fs,
+  io::{self, Write},
+  os::unix::{
+    fs::FileTypeExt,
+    net::{UnixListener, UnixStream},
+  },
+  path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+  app::App,
+  error::GameError,
+  frame::encode_frame,
+  game::{Coord, Status, COLS, ROWS},
+};
+
+/// game state published to external tools, boards are one string per row
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct GameSnapshot {
+  pub rule: String,
+  pub user_turn: bool,
+  pub winner: Option<String>,
+  pub volleys: usize,
+  pub ships_sunk: [usize; 2],
+  /// the player's fleet and what they know of the opponent's
+  pub boards: [Vec<String>; 2],
+}
+
+impl GameSnapshot {
+  pub fn new(app: &App) -> Self {
+    Self {
+      rule: app.rule().to_string(),
+      user_turn: app.is_user_turn(),
+      winner: app.winner_name().map(|n| n.to_string()),
+      volleys: app.volley_count(),
+      ships_sunk: app.ships_sunk(),
+      boards: [board_rows(app, true), board_rows(app, false)],
+    }
+  }
+
+  /// JSON behind its length, see `frame::FrameReader` to read it back
+  pub fn to_frame(&self) -> Vec<u8> {
+    encode_frame(&serde_json::to_vec(self).unwrap_or_default())
+  }
+}
+
+/// most bytes kept for a subscriber that stopped reading, it's dropped past this
+const MAX_PENDING: usize = 64 * 1024;
+/// sends in a row a subscriber may take nothing before it's dropped
+const SEND_RETRIES: u32 = 3;
+
+/// bytes waiting for a non-blocking writer, what it doesn't take now is retried on the next send
+/// instead of waiting, so frames are never cut short and the UI never sleeps
+struct Outbox<W> {
+  writer: W,
+  pending: Vec<u8>,
+  /// sends in a row that wrote nothing
+  stalls: u32,
+}
+
+impl<W: Write> Outbox<W> {
+  fn new(writer: W) -> Self {
+    Self {
+      writer,
+      pending: vec![],
+      stalls: 0,
+    }
+  }
+
+  /// never waits, fails when the writer went away, stalled too often or fell too far behind
+  fn send(&mut self, bytes: &[u8]) -> io::Result<()> {
+    self.pending.extend_from_slice(bytes);
+    let mut written = 0;
+    while written < self.pending.len() {
+      match self.writer.write(&self.pending[written..]) {
+        Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+        Ok(n) => written += n,
+        Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+        Err(e) => return Err(e),
+      }
+    }
+    self.pending.drain(..written);
+    self.stalls = if written == 0 && !self.pending.is_empty() {
+      self.stalls + 1
+    } else {
+      0
+    };
+    if self.stalls > SEND_RETRIES || self.pending.len() > MAX_PENDING {
+      return Err(io::Error::new(
+        io::ErrorKind::WouldBlock,
+        "subscriber stopped reading",
+      ));
+    }
+    self.writer.flush()
+  }
+}
+
+/// unix socket that sends every connected subscriber a snapshot after each volley
+pub struct StateSocket {
+  path: PathBuf,
+  listener: UnixListener,
+  subscribers: Vec<Outbox<UnixStream>>,
+}
+
+impl StateSocket {
+  pub fn bind(path: &Path) -> Result<Self, GameError> {
+    Ok(Self {
+      path: path.to_path_buf(),
+      listener: listen(path).map_err(GameError::Network)?,
+      subscribers: vec![],
+    })
+  }
+
+  /// never blocks, subscribers that can't keep up or went away are dropped and the first of
+  /// their errors returned
+  pub fn broadcast(&mut self, snapshot: &GameSnapshot) -> Result<(), GameError> {
+    while let Ok((stream, _)) = self.listener.accept() {
+      if stream.set_nonblocking(true).is_ok() {
+        self.subscribers.push(Outbox::new(stream));
+      }
+    }
+    let frame = snapshot.to_frame();
+    let mut error = None;
+    self.subscribers.retain_mut(|s| match s.send(&frame) {
+      Ok(()) => true,
+      Err(e) => {
+        error.get_or_insert(e);
+        false
+      }
+    });
+    error.map_or(Ok(()), |e| Err(GameError::Network(e)))
+  }
+}
+
+fn listen(path: &Path) -> io::Result<UnixListener> {
+  // a socket left behind by an earlier run, never remove anything else
+  if fs::metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+    fs::remove_file(path)?;
+  }
+  let listener = UnixListener::bind(path)?;
+  listener.set_nonblocking(true)?;
+  Ok(listener)
+}
+
+impl Drop for StateSocket {
+  fn drop(&mut self) {
+    let _ = fs::remove_file(&self.path);
+  }
+}
+
+fn board_rows(app: &App, is_self: bool) -> Vec<String> {
+  (0..ROWS)
+    .map(|r| {
+      (0..COLS)
+        .filter_map(|c| Coord::new(r, c))
+        .map(|c| match app.cell(c, is_self).view().status {
+          Status::Live => 'L',
+          Status::Miss => 'M',
+          Status::Hit => 'H',
+          Status::Kill => 'K',
+          Status::Space => '.',
+        })
+        .collect::<String>()
+    })
+    .collect::<Vec<_>>()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::game::{Difficulty, Game, Rule};
+
+  fn from_json(json: &str) -> Option<GameSnapshot> {
+    serde_json::from_str(json).ok()
+  }
+
+  #[test]
+  fn test_snapshot_json_roundtrip() {
+    let mut game = Game::with_seed(Rule::Fury, Difficulty::Easy, 1);
+    game.player_mut().set_name(Some("A \"quoted\" name".into()));
+    while !game.is_won() {
+      game.bot_fire();
+    }
+    let app = App::new("test".into(), game);
+    let snapshot = GameSnapshot::new(&app);
+    assert!(snapshot.winner.is_some());
+    // the loser's fleet is sunk, whichever side that is
+    assert!(snapshot.boards.iter().flatten().any(|r| r.contains('K')));
+
+    let frame = snapshot.to_frame();
+    let json = String::from_utf8(frame[4..].to_vec()).unwrap();
+    assert_eq!(frame[..4], (json.len() as u32).to_le_bytes());
+    assert_eq!(from_json(&json), Some(snapshot));
+
+    assert!(from_json("{\"rule\":\"Fury\"}").is_none());
+    assert!(from_json(&json[1..]).is_none());
+  }
+
+  /// takes a few bytes per write and is full on every other one
+  struct Choppy {
+    written: Vec<u8>,
+    full: bool,
+  }
+
+  impl Write for Choppy {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+      self.full = !self.full;
+      if self.full {
+        return Err(io::ErrorKind::WouldBlock.into());
+      }
+      let n = buf.len().min(7);
+      self.written.extend(&buf[..n]);
+      Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_outbox_partial_writes() {
+    let app = App::new("test".into(), Game::new(Rule::Default, Difficulty::Easy));
+    let line = GameSnapshot::new(&app).to_frame();
+
+    // whatever a full writer doesn't take waits for the next send instead of being lost
+    let mut outbox = Outbox::new(Choppy {
+      written: vec![],
+      full: false,
+    });
+    outbox.send(&line).unwrap();
+    assert!(!outbox.pending.is_empty());
+    assert_eq!(
+      outbox.writer.written.len() + outbox.pending.len(),
+      line.len()
+    );
+    while !outbox.pending.is_empty() {
+      outbox.send(&[]).unwrap();
+    }
+    assert_eq!(outbox.writer.written, line);
+
+    // one that stays full is given up on once too much piles up
+    struct Full;
+    impl Write for Full {
+      fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::ErrorKind::WouldBlock.into())
+      }
+      fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+      }
+    }
+    let mut outbox = Outbox::new(Full);
+    for _ in 0..SEND_RETRIES {
+      outbox.send(&line).unwrap();
+    }
+    assert_eq!(
+      outbox.send(&line).unwrap_err().kind(),
+      io::ErrorKind::WouldBlock
+    );
+  }
+
+  #[test]
+  fn test_state_socket_broadcast() {
+    use crate::frame::FrameReader;
+
+    let path = std::env::temp_dir().join(format!("battleship-{}.sock", std::process::id()));
+    let mut socket = StateSocket::bind(&path).unwrap();
+    let app = App::new("test".into(), Game::new(Rule::Default, Difficulty::Easy));
+    let snapshot = GameSnapshot::new(&app);
+
+    // nobody listening yet
+    socket.broadcast(&snapshot).unwrap();
+
+    let subscriber = UnixStream::connect(&path).unwrap();
+    socket.broadcast(&snapshot).unwrap();
+    let json = FrameReader::new(&subscriber).read_frame().unwrap().unwrap();
+    assert_eq!(
+      from_json(&String::from_utf8(json).unwrap()),
+      Some(snapshot.clone())
+    );
+
+    // a subscriber that went away is dropped without blocking and reported
+    drop(subscriber);
+    let errors = (0..2)
+      .filter(|_| matches!(socket.broadcast(&snapshot), Err(GameError::Network(_))))
+      .count();
+    assert_eq!(errors, 1);
+    assert!(socket.subscribers.is_empty());
+
+    drop(socket);
+    assert!(!path.exists());
+  }
+}
@@ -0,0 +1,109 @@
+use std::{
+  collections::BTreeSet,
+  io::{self, BufRead, Write},
+};
+
+use super::game::{parse_coordinate, Board, Coord, Game, Status, COLS, ROWS};
+
+/// play against the computer with plain lines on `input` and `output`, no raw mode or terminal UI
+pub fn play<R: BufRead, W: Write>(game: &mut Game, input: R, output: &mut W) -> io::Result<()> {
+  let mut lines = input.lines();
+  while !game.is_won() {
+    if !game.is_user_turn() {
+      writeln!(output, "{}", game.bot_fire())?;
+      continue;
+    }
+    writeln!(output, "{}", tracking_text(game.player().opponent_board()))?;
+    let max_shots = game.max_shots();
+    if max_shots == 1 {
+      write!(output, "Enter shot (e.g. C7): ")?;
+    } else {
+      write!(output, "Enter up to {} shots (e.g. C7 D2): ", max_shots)?;
+    }
+    output.flush()?;
+    let line = match lines.next() {
+      Some(line) => line?,
+      None => break,
+    };
+    if line.trim() == "q" {
+      break;
+    }
+    match parse_shots(&line, max_shots) {
+      Ok(shots) => writeln!(output, "{}", game.fire(&shots, false))?,
+      Err(msg) => writeln!(output, "{}", msg)?,
+    }
+  }
+  Ok(())
+}
+
+/// cells separated by spaces or commas, no more than the rule allows
+fn parse_shots(line: &str, max_shots: usize) -> Result<BTreeSet<Coord>, String> {
+  let shots = line
+    .split(|c: char| c.is_whitespace() || c == ',')
+    .filter(|s| !s.is_empty())
+    .map(|s| parse_coordinate(s).ok_or(format!("Not a cell: {}", s)))
+    .collect::<Result<BTreeSet<_>, _>>()?;
+  if shots.is_empty() {
+    Err("Enter a cell like C7, or q to quit".into())
+  } else if shots.len() > max_shots {
+    Err(format!("At most {} shots this turn", max_shots))
+  } else {
+    Ok(shots)
+  }
+}
+
+/// the tracking board with row letters and column numbers, in plain ascii
+fn tracking_text(board: &Board) -> String {
+  let header = (1..=COLS).map(|c| format!("{:>3}", c)).collect::<String>();
+  std::iter::once(format!(" {}", header))
+    .chain((0..ROWS).filter_map(|row| {
+      let cells = (0..COLS)
+        .map(|col| {
+          let cell = match board.cell_info(Coord::new(row, col)?).status {
+            Status::Miss => 'o',
+            Status::Hit | Status::Live => 'x',
+            Status::Kill => '#',
+            Status::Space => '.',
+          };
+          Some(format!("  {}", cell))
+        })
+        .collect::<Option<String>>()?;
+      Some(format!("{}{}", (b'A' + row as u8) as char, cells))
+    }))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::game::{Difficulty, Rule};
+
+  #[test]
+  fn test_text_play() {
+    assert_eq!(parse_coordinate("c7"), Coord::new(2, 6));
+    assert_eq!(parse_coordinate("K1"), None);
+
+    let mut game = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    let mut output = vec![];
+    play(&mut game, "Z9\nA1\nq\n".as_bytes(), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    // the bad cell is refused, the good one fired and answered by the computer
+    assert!(
+      output.contains("Enter shot (e.g. C7): Not a cell: Z9"),
+      "{}",
+      output
+    );
+    assert_eq!(game.history().len(), 2);
+    let mut expected = Game::with_seed(Rule::Default, Difficulty::Easy, 1);
+    let message = expected.fire(&parse_shots("A1", 1).unwrap(), false);
+    assert!(
+      output.contains(&format!("Enter shot (e.g. C7): {}", message)),
+      "{}",
+      output
+    );
+    assert!(output.lines().any(|l| l.starts_with("A ")));
+  }
+}
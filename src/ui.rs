@@ -2,103 +2,291 @@ use tui::{
   backend::Backend,
   layout::{Alignment, Constraint, Direction, Layout, Rect},
   style::{Color, Modifier, Style},
+  text::{Line, Span},
   widgets::{Block, BorderType, Borders, Clear, Paragraph},
   Frame,
 };
 
 use super::{
-  game::{COLS, ROWS},
+  game::{Coord, Status, COLS, ROWS},
+  render::{CellView, GridView, Palette, Renderer, Screen},
   App,
 };
 
-const CELL_WIDTH: u16 = 5;
 const CELL_HEIGHT: u16 = 3;
 const PADDING: u16 = 1;
-const GRID_WIDTH: u16 = CELL_WIDTH * (COLS as u16) + 2 * PADDING;
+/// the history panel only shows when there's room for it next to both boards
+const HISTORY_WIDTH: u16 = 32;
 const GRID_HEIGHT: u16 = CELL_HEIGHT * (ROWS as u16) + 2 * PADDING;
 
-pub fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App) {
-  let main_block = Block::default()
-    .borders(Borders::ALL)
-    .style(Style::default().bg(Color::Black).fg(Color::Cyan))
-    .title(format!(
-      "{} | Rule: {} ({}s)",
-      app.title,
-      app.rule(),
-      app.elapsed_duration(),
-    ));
+/// draws a `Screen` on a tui frame
+pub struct TuiRenderer<'a, 'f, B: Backend> {
+  frame: &'a mut Frame<'f, B>,
+}
 
-  f.render_widget(main_block, f.size());
+impl<'a, 'f, B: Backend> TuiRenderer<'a, 'f, B> {
+  pub fn new(frame: &'a mut Frame<'f, B>) -> Self {
+    Self { frame }
+  }
+}
 
-  let vertical_pad_block_height = f.size().height.checked_sub(GRID_HEIGHT).unwrap_or_default() / 2;
-  let v_chunks = Layout::default()
-    .direction(Direction::Vertical)
-    .constraints(vec![
-      Constraint::Min(vertical_pad_block_height),
-      Constraint::Length(GRID_HEIGHT + 1),
-      Constraint::Min(vertical_pad_block_height),
+impl<B: Backend> Renderer for TuiRenderer<'_, '_, B> {
+  fn render(&mut self, screen: &Screen) {
+    let f = &mut *self.frame;
+    let main_block = Block::default()
+      .borders(Borders::ALL)
+      .style(Style::default().bg(Color::Black).fg(Color::Cyan))
+      .title(screen.title.as_str());
+
+    f.render_widget(main_block, f.size());
+
+    let layout = ScreenLayout::new(f.size(), screen.accessible);
+
+    let header = Paragraph::new(vec![
+      Line::styled(
+        screen.last_message.as_str(),
+        Style::default().fg(Color::DarkGray),
+      ),
+      Line::from(screen.help.as_str()),
     ])
-    .split(f.size());
+    .style(Style::default().fg(Color::Gray))
+    .block(Block::default().borders(Borders::NONE))
+    .alignment(Alignment::Center);
 
-  let header = Paragraph::new(
-    "move: 🠔 🠗 🠕 🠖 (or) hjkl | select/unselect: <space> | fire: <enter> | quit: <q>",
-  )
-  .style(Style::default().fg(Color::Gray))
-  .block(Block::default().borders(Borders::NONE))
-  .alignment(Alignment::Center);
+    f.render_widget(header, layout.header);
 
-  f.render_widget(header, v_chunks[2]);
+    if let Some(area) = layout.history {
+      draw_history(f, area, &screen.ships, &screen.history);
+    }
 
-  let board_chunks = Layout::default()
-    .direction(Direction::Horizontal)
-    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-    .split(v_chunks[1]);
+    draw_board(f, layout.boards[0], &screen.grids[0], screen);
+    draw_board(f, layout.boards[1], &screen.grids[1], screen);
 
-  let player_chunk = board_chunks[0];
-  let opponent_chunk = board_chunks[1];
+    // show alerts
+    draw_alert(f, screen.status.clone(), f.size());
 
-  draw_board(f, player_chunk, "You", app, true);
-  draw_board(f, opponent_chunk, "Computer", app, false);
+    if let Some(result) = &screen.result {
+      draw_popup(f, " Game over ", result, f.size());
+    }
 
-  // show alerts
-  if app.frame_count % 8 != 0 || app.is_won() {
-    draw_alert(f, app.message.clone(), f.size());
-  } else {
+    if let Some(overlay) = &screen.overlay {
+      draw_popup(f, " Help ", overlay, f.size());
+    }
+  }
+}
+
+pub fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+  // alerts stay up for 2 seconds unless the game is over
+  if app.frame_count.is_multiple_of(8) && !app.is_won() {
     // reset messages
     app.message = String::default();
   }
+
+  TuiRenderer::new(f).render(&Screen::new(app));
+}
+
+/// where the parts of the screen go, drawing and mouse clicks both go by it
+struct ScreenLayout {
+  header: Rect,
+  history: Option<Rect>,
+  /// your own board on the left, the opponent's on the right
+  boards: [Rect; 2],
+}
+
+impl ScreenLayout {
+  fn new(size: Rect, accessible: bool) -> Self {
+    let vertical_pad_block_height = size.height.saturating_sub(GRID_HEIGHT) / 2;
+    let v_chunks = Layout::default()
+      .direction(Direction::Vertical)
+      .constraints(vec![
+        Constraint::Min(vertical_pad_block_height),
+        Constraint::Length(GRID_HEIGHT + 1),
+        Constraint::Min(vertical_pad_block_height),
+      ])
+      .split(size);
+
+    let show_history = size.width >= 2 * grid_width(accessible) + HISTORY_WIDTH;
+    let main_chunks = Layout::default()
+      .direction(Direction::Horizontal)
+      .constraints(
+        [
+          Constraint::Min(0),
+          Constraint::Length(if show_history { HISTORY_WIDTH } else { 0 }),
+        ]
+        .as_ref(),
+      )
+      .split(v_chunks[1]);
+    let board_chunks = Layout::default()
+      .direction(Direction::Horizontal)
+      .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+      .split(main_chunks[0]);
+
+    Self {
+      header: v_chunks[2],
+      history: show_history.then_some(main_chunks[1]),
+      boards: [board_chunks[0], board_chunks[1]],
+    }
+  }
+}
+
+/// the board under a mouse click at column `x` and row `y` of the terminal, counted from 0, and
+/// the cell clicked, 0 is your own board and 1 the opponent's
+pub fn cell_at(
+  size: Rect,
+  accessible: bool,
+  grid_lines: bool,
+  x: u16,
+  y: u16,
+) -> Option<(usize, Coord)> {
+  ScreenLayout::new(size, accessible)
+    .boards
+    .iter()
+    .enumerate()
+    .find_map(|(board, chunk)| {
+      let (_, board_rect) = board_layout(*chunk, accessible);
+      let cells = if grid_lines {
+        grid_lines_cell_rects(board_rect, accessible)
+      } else {
+        cell_rects(board_rect, accessible)
+      };
+      cells.iter().enumerate().find_map(|(row, rects)| {
+        let col = rects
+          .iter()
+          .position(|r| (r.x..r.x + r.width).contains(&x) && (r.y..r.y + r.height).contains(&y))?;
+        Some((board, Coord::new(row, col)?))
+      })
+    })
 }
 
-fn draw_board<B: Backend>(
-  f: &mut Frame<B>,
-  player_chunk: Rect,
-  title: &str,
-  app: &mut App,
-  is_self: bool,
-) {
-  let row_constraints = std::iter::repeat(Constraint::Length(CELL_HEIGHT))
-    .take(ROWS)
-    .collect::<Vec<_>>();
-  let col_constraints = std::iter::repeat(Constraint::Length(CELL_WIDTH))
-    .take(COLS)
-    .collect::<Vec<_>>();
-
-  let horizontal_pad_block_width = (player_chunk.width - GRID_WIDTH) / 2;
+/// the title and the grid of a board, centered in its half of the screen
+fn board_layout(chunk: Rect, accessible: bool) -> (Rect, Rect) {
+  let grid_width = grid_width(accessible);
+  let horizontal_pad_block_width = chunk.width.saturating_sub(grid_width) / 2;
   let h_main_rects = Layout::default()
     .direction(Direction::Horizontal)
     .constraints(vec![
       Constraint::Min(horizontal_pad_block_width),
-      Constraint::Length(GRID_WIDTH),
+      Constraint::Length(grid_width),
       Constraint::Min(horizontal_pad_block_width),
     ])
-    .split(player_chunk);
+    .split(chunk);
 
   let v_main_rects = Layout::default()
     .direction(Direction::Vertical)
     .constraints(vec![Constraint::Min(1), Constraint::Length(GRID_HEIGHT)])
     .split(h_main_rects[1]);
+  (v_main_rects[0], v_main_rects[1])
+}
+
+/// each cell's box inside the board's border, row by row
+fn cell_rects(board_rect: Rect, accessible: bool) -> Vec<Vec<Rect>> {
+  let row_constraints =
+    std::iter::repeat_n(Constraint::Length(CELL_HEIGHT), ROWS).collect::<Vec<_>>();
+  let col_constraints =
+    std::iter::repeat_n(Constraint::Length(cell_width(accessible)), COLS).collect::<Vec<_>>();
+  Layout::default()
+    .direction(Direction::Vertical)
+    .vertical_margin(1)
+    .horizontal_margin(0)
+    .constraints(row_constraints)
+    .split(board_rect)
+    .iter()
+    .map(|row_rect| {
+      Layout::default()
+        .direction(Direction::Horizontal)
+        .vertical_margin(0)
+        .horizontal_margin(1)
+        .constraints(col_constraints.clone())
+        .split(*row_rect)
+        .to_vec()
+    })
+    .collect()
+}
+
+/// each cell's label in grid lines mode, the grid is centered like a paragraph centers a line
+fn grid_lines_cell_rects(area: Rect, accessible: bool) -> Vec<Vec<Rect>> {
+  let width = grid_lines_cell_width(accessible) as u16;
+  let line_width = (width + 1) * COLS as u16 + 1;
+  let left = area.x + (area.width / 2).saturating_sub(line_width / 2);
+  (0..ROWS as u16)
+    .map(|row| {
+      (0..COLS as u16)
+        .map(|col| Rect::new(left + 1 + col * (width + 1), area.y + 1 + 2 * row, width, 1))
+        .collect()
+    })
+    .collect()
+}
+
+/// the widest label any cell can show, an emoji or a four letter word
+fn label_width(accessible: bool) -> usize {
+  Status::ALL
+    .iter()
+    .map(|s| s.label_width(accessible))
+    .max()
+    .unwrap_or(1)
+}
+
+/// label width of a cell in grid lines mode, the lines sit right next to the labels
+fn grid_lines_cell_width(accessible: bool) -> usize {
+  label_width(accessible)
+}
+
+/// the widest label between two borders, emoji get a spare column as terminals disagree on
+/// their width
+fn cell_width(accessible: bool) -> u16 {
+  let spare = if accessible { 0 } else { 1 };
+  (label_width(accessible) + 2 + spare) as u16
+}
 
-  let title = Paragraph::new(title)
+fn grid_width(accessible: bool) -> u16 {
+  cell_width(accessible) * (COLS as u16) + 2 * PADDING
+}
+
+/// the latest volleys, the newest at the bottom
+fn draw_history<B: Backend>(f: &mut Frame<B>, area: Rect, ships: &[String], history: &[String]) {
+  let text = if history.is_empty() {
+    "No shots yet".to_string()
+  } else {
+    // ship states go first, keep the newest volleys when they don't all fit below
+    let header = if ships.is_empty() {
+      vec![]
+    } else {
+      ships
+        .iter()
+        .cloned()
+        .chain(std::iter::once(String::default()))
+        .collect()
+    };
+    let rows = usize::from(area.height.saturating_sub(2)).saturating_sub(header.len());
+    header
+      .into_iter()
+      .chain(
+        history[history.len().saturating_sub(rows)..]
+          .iter()
+          .cloned(),
+      )
+      .collect::<Vec<_>>()
+      .join("\n")
+  };
+  f.render_widget(
+    Paragraph::new(text)
+      .style(Style::default().fg(Color::Gray))
+      .block(
+        Block::default()
+          .borders(Borders::ALL)
+          .border_type(BorderType::Plain)
+          .title(" History "),
+      ),
+    area,
+  );
+}
+
+fn draw_board<B: Backend>(f: &mut Frame<B>, player_chunk: Rect, grid: &GridView, screen: &Screen) {
+  let accessible = screen.accessible;
+  let cell_width = cell_width(accessible);
+  let (title_rect, board_rect) = board_layout(player_chunk, accessible);
+
+  let title = Paragraph::new(grid.title.as_str())
     .style(
       Style::default()
         .fg(Color::Green)
@@ -107,63 +295,174 @@ fn draw_board<B: Backend>(
     .block(Block::default().borders(Borders::NONE))
     .alignment(Alignment::Center);
 
-  f.render_widget(title, v_main_rects[0]);
+  f.render_widget(title, title_rect);
+
+  if screen.grid_lines {
+    draw_grid_lines(f, board_rect, grid, screen);
+    return;
+  }
 
   let board_block = Block::default()
     .borders(Borders::ALL)
     .border_type(BorderType::Plain);
 
-  let board_rect = v_main_rects[1];
   f.render_widget(board_block, board_rect);
 
-  let row_rects = Layout::default()
-    .direction(Direction::Vertical)
-    .vertical_margin(1)
-    .horizontal_margin(0)
-    .constraints(row_constraints)
-    .split(board_rect);
-
-  for (r, row_rect) in row_rects.iter().enumerate() {
-    let col_rects = Layout::default()
-      .direction(Direction::Horizontal)
-      .vertical_margin(0)
-      .horizontal_margin(1)
-      .constraints(col_constraints.clone())
-      .split(*row_rect);
-
-    for (c, cell_rect) in col_rects.iter().enumerate() {
-      let cell = app.cell((r, c), is_self);
-      let single_row_text = format!(
-        "{:^length$}",
-        cell.to_string(),
-        length = usize::from(CELL_WIDTH - 2)
-      );
-      let pad_line = " ".repeat(usize::from(CELL_WIDTH));
+  for (col_rects, row) in cell_rects(board_rect, accessible)
+    .iter()
+    .zip(grid.cells.iter())
+  {
+    for (cell_rect, cell) in col_rects.iter().zip(row.iter()) {
+      let single_row_text = padded_label(cell, accessible, usize::from(cell_width - 2));
+      let pad_line = " ".repeat(usize::from(cell_width));
 
       // 1 line for the text, 1 line each for the top and bottom of the cell == 3 lines
       // that are not eligible for padding
-      let num_pad_lines = usize::from(CELL_HEIGHT.checked_sub(3).unwrap_or_default());
+      let num_pad_lines = usize::from(CELL_HEIGHT.saturating_sub(3));
 
       // text is:
       //   pad with half the pad lines budget
       //   the interesting text
       //   pad with half the pad lines budget
       //   join with newlines
-      let text = std::iter::repeat(pad_line.clone())
-        .take(num_pad_lines / 2)
+      let text = std::iter::repeat_n(pad_line.clone(), num_pad_lines / 2)
         .chain(std::iter::once(single_row_text.clone()))
-        .chain(std::iter::repeat(pad_line).take(num_pad_lines / 2))
+        .chain(std::iter::repeat_n(pad_line, num_pad_lines / 2))
         .collect::<Vec<_>>()
         .join("\n");
 
       let cell_text = Paragraph::new(text)
-        .block(cell.block())
-        .style(cell.text_style());
+        .block(cell_block(cell, screen.palette))
+        .style(cell_text_style(cell));
       f.render_widget(cell_text, *cell_rect);
     }
   }
 }
 
+/// the cell's label centered in `width` columns, emoji are double width so this goes by display
+/// width instead of char count
+fn padded_label(cell: &CellView, accessible: bool, width: usize) -> String {
+  // water stays blank, it's most of the board
+  let label = if accessible && cell.status != Status::Space {
+    cell.status.label(true)
+  } else {
+    cell.status.to_string()
+  };
+  let pad = width.saturating_sub(cell.status.label_width(accessible));
+  format!(
+    "{}{}{}",
+    " ".repeat(pad / 2),
+    label,
+    " ".repeat(pad - pad / 2)
+  )
+}
+
+/// a board as box drawing lines around and between the cells
+fn draw_grid_lines<B: Backend>(f: &mut Frame<B>, area: Rect, grid: &GridView, screen: &Screen) {
+  let width = grid_lines_cell_width(screen.accessible);
+  let mut lines = vec![Line::from(grid_line(['┌', '┬', '┐'], width))];
+  for (i, row) in grid.cells.iter().enumerate() {
+    if i > 0 {
+      lines.push(Line::from(grid_line(['├', '┼', '┤'], width)));
+    }
+    lines.push(bordered_row(row, screen.accessible, screen.palette, width));
+  }
+  lines.push(Line::from(grid_line(['└', '┴', '┘'], width)));
+  f.render_widget(
+    Paragraph::new(lines)
+      .style(Style::default().bg(Color::Black).fg(Color::Gray))
+      .alignment(Alignment::Center),
+    area,
+  );
+}
+
+/// a border across the board, `corners` are the left end, the crossings and the right end
+fn grid_line(corners: [char; 3], width: usize) -> String {
+  let [left, cross, right] = corners;
+  let cell = "─".repeat(width);
+  let inner = vec![cell; COLS].join(&cross.to_string());
+  format!("{}{}{}", left, inner, right)
+}
+
+/// the cells of a row between vertical lines, each colored like its border in box mode
+fn bordered_row(
+  row: &[CellView],
+  accessible: bool,
+  palette: Palette,
+  width: usize,
+) -> Line<'static> {
+  let mut spans = vec![Span::raw("│")];
+  for cell in row {
+    spans.push(Span::styled(
+      padded_label(cell, accessible, width),
+      cell_text_style(cell).fg(cell_color(cell, palette)),
+    ));
+    spans.push(Span::raw("│"));
+  }
+  Line::from(spans)
+}
+
+/// border color of a cell showing `status`
+fn status_color(palette: Palette, status: Status) -> Color {
+  match (palette, status) {
+    (Palette::Default, Status::Live) => Color::Yellow,
+    (Palette::Default, Status::Hit | Status::Kill) => Color::Red,
+    (Palette::Default, Status::Miss | Status::Space) => Color::White,
+    // blue against orange tells ships from hits without relying on red and green
+    (Palette::HighContrast, Status::Live) => Color::LightBlue,
+    (Palette::HighContrast, Status::Hit) => Color::Indexed(208),
+    (Palette::HighContrast, Status::Kill) => Color::LightMagenta,
+    (Palette::HighContrast, Status::Miss | Status::Space) => Color::White,
+    // grayscale ramp of the 256 color palette, from dark to light
+    (Palette::Monochrome, Status::Space) => Color::Indexed(240),
+    (Palette::Monochrome, Status::Miss) => Color::Indexed(244),
+    (Palette::Monochrome, Status::Kill) => Color::Indexed(248),
+    (Palette::Monochrome, Status::Hit) => Color::Indexed(252),
+    (Palette::Monochrome, Status::Live) => Color::Indexed(255),
+  }
+}
+
+/// cell border color, or the label color with grid lines
+fn cell_color(cell: &CellView, palette: Palette) -> Color {
+  if cell.preview == Some(false) {
+    Color::Red
+  } else if cell.preview == Some(true) {
+    Color::LightBlue
+  } else if cell.pending {
+    Color::Yellow
+  } else if cell.active {
+    Color::Cyan
+  } else if cell.hinted {
+    Color::LightGreen
+  } else if cell.ghost {
+    Color::DarkGray
+  } else if cell.region {
+    Color::Magenta
+  } else {
+    status_color(palette, cell.status)
+  }
+}
+
+fn cell_block(cell: &CellView, palette: Palette) -> Block<'_> {
+  Block::default()
+    .borders(Borders::ALL)
+    .style(
+      Style::default()
+        .bg(Color::Black)
+        .fg(cell_color(cell, palette)),
+    )
+    .border_type(BorderType::Rounded)
+}
+
+fn cell_text_style(cell: &CellView) -> Style {
+  // cell background color, pending shots are highlighted until fired
+  if cell.pending {
+    Style::default().bg(Color::DarkGray)
+  } else {
+    Style::default().bg(Color::Black)
+  }
+}
+
 fn draw_alert<B: Backend>(f: &mut Frame<B>, message: String, area: Rect) {
   if !message.is_empty() {
     let area = top_centered_rect(50, 4, area);
@@ -192,6 +491,25 @@ fn draw_alert<B: Backend>(f: &mut Frame<B>, message: String, area: Rect) {
   }
 }
 
+fn draw_popup<B: Backend>(f: &mut Frame<B>, title: &str, text: &str, area: Rect) {
+  let height = text.lines().count() as u16 + 2;
+  let area = top_centered_rect(60, height, area);
+  f.render_widget(Clear, area); //this clears out the background
+  f.render_widget(
+    Paragraph::new(text)
+      .block(
+        Block::default()
+          .title(title)
+          .borders(Borders::ALL)
+          .border_type(BorderType::Rounded)
+          .border_style(Style::default().fg(Color::Cyan)),
+      )
+      .alignment(Alignment::Center)
+      .style(Style::default().bg(Color::Black).fg(Color::White)),
+    area,
+  );
+}
+
 fn top_centered_rect(width: u16, height: u16, r: Rect) -> Rect {
   let Rect {
     width: grid_width,
@@ -199,13 +517,13 @@ fn top_centered_rect(width: u16, height: u16, r: Rect) -> Rect {
     ..
   } = r;
 
-  let outer_height = grid_height.checked_sub(height).unwrap_or_default();
+  let outer_height = grid_height.saturating_sub(height);
   let popup_layout = Layout::default()
     .direction(Direction::Vertical)
     .constraints([Constraint::Length(height), Constraint::Length(outer_height)].as_ref())
     .split(r);
 
-  let outer_width = (grid_width / 2).checked_sub(width / 2).unwrap_or_default();
+  let outer_width = (grid_width / 2).saturating_sub(width / 2);
 
   Layout::default()
     .direction(Direction::Horizontal)
@@ -219,3 +537,91 @@ fn top_centered_rect(width: u16, height: u16, r: Rect) -> Rect {
     )
     .split(popup_layout[0])[1]
 }
+
+#[cfg(test)]
+mod tests {
+  use std::collections::BTreeSet;
+
+  use super::*;
+
+  #[test]
+  fn test_status_color_monochrome() {
+    let statuses = [
+      Status::Live,
+      Status::Miss,
+      Status::Hit,
+      Status::Kill,
+      Status::Space,
+    ];
+    let grays = statuses
+      .iter()
+      .map(|s| match status_color(Palette::Monochrome, *s) {
+        // 232 to 255 are the grays of the 256 color palette
+        Color::Indexed(i) if i >= 232 => i,
+        color => panic!("{:?} isn't gray", color),
+      })
+      .collect::<BTreeSet<_>>();
+    assert_eq!(grays.len(), statuses.len());
+
+    // high contrast never shows hits in red
+    assert!(statuses
+      .iter()
+      .all(|s| status_color(Palette::HighContrast, *s) != Color::Red));
+  }
+
+  #[test]
+  fn test_cell_at() {
+    let size = Rect::new(0, 0, 200, 50);
+    let at = Coord::new(2, 3).unwrap();
+    for grid_lines in [false, true] {
+      let layout = ScreenLayout::new(size, false);
+      for (board, chunk) in layout.boards.iter().enumerate() {
+        let (_, board_rect) = board_layout(*chunk, false);
+        let cells = if grid_lines {
+          grid_lines_cell_rects(board_rect, false)
+        } else {
+          cell_rects(board_rect, false)
+        };
+        let cell = cells[2][3];
+        // anywhere inside the cell, the corner and the far edge alike
+        for (x, y) in [
+          (cell.x, cell.y),
+          (cell.x + cell.width - 1, cell.y + cell.height - 1),
+        ] {
+          assert_eq!(cell_at(size, false, grid_lines, x, y), Some((board, at)));
+        }
+      }
+      assert_eq!(cell_at(size, false, grid_lines, 0, 0), None);
+    }
+  }
+
+  #[test]
+  fn test_bordered_row() {
+    let cell = |status| CellView {
+      status,
+      active: false,
+      pending: false,
+      hinted: false,
+      ghost: false,
+      preview: None,
+      region: false,
+    };
+    let row = [cell(Status::Live), cell(Status::Space), cell(Status::Miss)];
+    let text = |accessible, width| {
+      bordered_row(&row, accessible, Palette::Default, width)
+        .spans
+        .iter()
+        .map(|s| s.content.to_string())
+        .collect::<String>()
+    };
+    // emoji fill their double width cells, the blank water is padded to match
+    assert_eq!(text(false, grid_lines_cell_width(false)), "│🚀│  │❌│");
+    assert_eq!(text(true, grid_lines_cell_width(true)), "│ship│    │miss│");
+    // the widths follow the labels instead of fixed numbers
+    assert_eq!((cell_width(false), cell_width(true)), (5, 6));
+    assert_eq!(
+      grid_line(['┌', '┬', '┐'], 2),
+      format!("┌{}──┐", "──┬".repeat(COLS - 1))
+    );
+  }
+}